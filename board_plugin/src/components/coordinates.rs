@@ -56,3 +56,15 @@ impl Display for Coordinates {
         write!(f, "({}, {})", self.x, self.y)
     }
 }
+
+impl From<minefield::Coordinates> for Coordinates {
+    fn from(minefield::Coordinates { x, y }: minefield::Coordinates) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<Coordinates> for minefield::Coordinates {
+    fn from(Coordinates { x, y }: Coordinates) -> Self {
+        Self { x, y }
+    }
+}
@@ -11,8 +11,11 @@ use bevy::{
 use bounds::Bounds2;
 use components::{Bomb, BombNeighbor, Coordinates, Uncover};
 use events::{BoardCompletedEvent, BombExplosionEvent, TileMarkEvent, TileTriggerEvent};
-use resources::{Board, tile::Tile, tile_map::TileMap};
-use settings_plugin::resources::{BoardAssets, BoardOptions, BoardPosition, TileSize};
+use minefield::{Generation, Minefield, Tile, random_seed};
+use resources::Board;
+use settings_plugin::resources::{
+    BoardAssets, BoardGeneration, BoardOptions, BoardPosition, TileSize,
+};
 
 pub struct BoardPlugin<T, U> {
     pub running_state: T,
@@ -59,26 +62,41 @@ impl<T, U> BoardPlugin<T, U> {
             None => BoardOptions::default(), // If no options is set we use the default one
             Some(o) => o.clone(),
         };
-        // Tilemap generation
-        let mut tile_map = TileMap::empty(options.map_size.0, options.map_size.1);
-        tile_map.set_bombs(options.bomb_count);
+        // Minefield generation
+        let seed = options.seed.unwrap_or_else(random_seed);
+        log::info!("Board seed: {}", seed);
+        let mut minefield = Minefield::empty(options.map_size.0, options.map_size.1)
+            .with_wrap_edges(options.wrap_edges);
+        let generation = match options.generation {
+            BoardGeneration::Random => Generation::Random,
+            BoardGeneration::NoGuess => Generation::NoGuess,
+        };
+        match minefield.generate(options.bomb_count, seed, generation, None) {
+            Ok(report) => {
+                log::info!("Bombs placed after {} attempt(s)", report.attempts);
+                if generation == Generation::NoGuess && !report.solvable {
+                    log::warn!("No solvable layout found, the board may require guessing");
+                }
+            }
+            Err(e) => log::error!("No bombs placed: {}", e),
+        }
         #[cfg(feature = "debug")]
-        // Tilemap debugging
-        log::info!("{}", tile_map.console_output());
+        // Minefield debugging
+        log::info!("{}", minefield.console_output());
 
         let tile_size = match options.tile_size {
             TileSize::Fixed(v) => v,
             TileSize::Adaptive { min, max } => Self::adaptative_tile_size(
                 window,
                 (min, max),
-                (tile_map.width(), tile_map.height()),
+                (minefield.width(), minefield.height()),
             ),
         };
 
         // We deduce the size of the complete board
         let board_size = Vec2::new(
-            tile_map.width() as f32 * tile_size,
-            tile_map.height() as f32 * tile_size,
+            minefield.width() as f32 * tile_size,
+            minefield.height() as f32 * tile_size,
         );
         log::info!("board size: {}", board_size);
         // We define the board anchor position (bottom left)
@@ -89,9 +107,7 @@ impl<T, U> BoardPlugin<T, U> {
             BoardPosition::Custom(p) => p,
         };
 
-        let mut covered_tiles =
-            HashMap::with_capacity((tile_map.width() * tile_map.height()).into());
-        let mut safe_start = None;
+        let mut covered_tiles = HashMap::with_capacity(minefield.area());
 
         let board_entity = commands
             .spawn((
@@ -114,24 +130,18 @@ impl<T, U> BoardPlugin<T, U> {
 
                 Self::spawn_tiles(
                     parent,
-                    &tile_map,
+                    &minefield,
                     tile_size,
                     options.tile_padding,
                     &board_assets,
                     &mut covered_tiles,
-                    &mut safe_start,
                 );
             })
             .id();
 
-        if options.safe_start {
-            if let Some(entity) = safe_start {
-                commands.entity(entity).insert(Uncover);
-            }
-        }
-
-        commands.insert_resource(Board {
-            tile_map,
+        let mut board = Board {
+            minefield,
+            seed,
             bounds: Bounds2 {
                 position: board_position.xy(),
                 size: board_size,
@@ -139,8 +149,18 @@ impl<T, U> BoardPlugin<T, U> {
             tile_size,
             covered_tiles,
             entity: board_entity,
-            marked_tiles: Vec::new(),
-        });
+        };
+
+        // A no guess board is only solvable from its start, so we always open it
+        if (options.safe_start || generation == Generation::NoGuess)
+            && let Some(coords) = board.minefield.safe_start()
+        {
+            for entity in board.uncover_tile(&coords.into()) {
+                commands.entity(entity).insert(Uncover);
+            }
+        }
+
+        commands.insert_resource(board);
     }
 
     /// Computes a tile size that matches the window according to the tile map size
@@ -174,86 +194,77 @@ impl<T, U> BoardPlugin<T, U> {
 
     fn spawn_tiles(
         parent: &mut RelatedSpawnerCommands<'_, ChildOf>,
-        tile_map: &TileMap,
+        minefield: &Minefield,
         size: f32,
         padding: f32,
         board_assets: &BoardAssets,
         covered_tiles: &mut HashMap<Coordinates, Entity>,
-        safe_start_entity: &mut Option<Entity>,
     ) {
         // Tiles
-        for (y, line) in tile_map.iter().enumerate() {
-            for (x, tile) in line.iter().enumerate() {
-                let coordinates = Coordinates {
-                    x: x as u16,
-                    y: y as u16,
-                };
+        for (coords, tile) in minefield.tiles() {
+            let coordinates: Coordinates = coords.into();
+            let Coordinates { x, y } = coordinates;
+            let mut cmd = parent.spawn((
+                Name::new(format!("Tile ({}, {})", x, y)),
+                Sprite {
+                    color: board_assets.tile_material.color,
+                    custom_size: Some(Vec2::splat(size - padding)),
+                    image: board_assets.tile_material.texture.clone(),
+                    ..default()
+                },
+                Transform::from_xyz(
+                    (x as f32 * size) + (size / 2.),
+                    (y as f32 * size) + (size / 2.),
+                    1.,
+                ),
+                coordinates,
+            ));
 
-                let mut cmd = parent.spawn((
-                    Name::new(format!("Tile ({}, {})", x, y)),
-                    Sprite {
-                        color: board_assets.tile_material.color,
-                        custom_size: Some(Vec2::splat(size - padding as f32)),
-                        image: board_assets.tile_material.texture.clone(),
-                        ..default()
-                    },
-                    Transform::from_xyz(
-                        (x as f32 * size) + (size / 2.),
-                        (y as f32 * size) + (size / 2.),
-                        1.,
-                    ),
-                    coordinates,
-                ));
+            // We add the cover sprites
+            cmd.with_children(|parent| {
+                let entity = parent
+                    .spawn((
+                        Name::new("Tile Cover"),
+                        Sprite {
+                            custom_size: Some(Vec2::splat(size - padding)),
+                            color: board_assets.covered_tile_material.color,
+                            image: board_assets.covered_tile_material.texture.clone(),
+                            ..default()
+                        },
+                        Transform::from_xyz(0., 0., 2.),
+                    ))
+                    .id();
+                covered_tiles.insert(coordinates, entity);
+            });
 
-                // We add the cover sprites
-                cmd.with_children(|parent| {
-                    let entity = parent
-                        .spawn((
-                            Name::new("Tile Cover"),
+            match tile {
+                // If the tile is a bomb we add the matching component and a sprite child
+                Tile::Bomb => {
+                    cmd.insert(Bomb);
+                    cmd.with_children(|parent| {
+                        parent.spawn((
                             Sprite {
+                                color: board_assets.bomb_material.color,
+                                image: board_assets.bomb_material.texture.clone(),
                                 custom_size: Some(Vec2::splat(size - padding)),
-                                color: board_assets.covered_tile_material.color,
-                                image: board_assets.covered_tile_material.texture.clone(),
                                 ..default()
                             },
-                            Transform::from_xyz(0., 0., 2.),
-                        ))
-                        .id();
-                    covered_tiles.insert(coordinates, entity);
-                    if safe_start_entity.is_none() && *tile == Tile::Empty {
-                        *safe_start_entity = Some(entity);
-                    }
-                });
-
-                match tile {
-                    // If the tile is a bomb we add the matching component and a sprite child
-                    Tile::Bomb => {
-                        cmd.insert(Bomb);
-                        cmd.with_children(|parent| {
-                            parent.spawn((
-                                Sprite {
-                                    color: board_assets.bomb_material.color,
-                                    image: board_assets.bomb_material.texture.clone(),
-                                    custom_size: Some(Vec2::splat(size - padding)),
-                                    ..default()
-                                },
-                                Transform::from_xyz(0., 0., 1.),
-                            ));
-                        });
-                    }
-                    // If the tile is a bomb neighbour we add the matching component and a text child
-                    Tile::BombNeighbor(v) => {
-                        cmd.insert(BombNeighbor { count: *v });
-                        cmd.with_children(|parent| {
-                            parent.spawn(Self::bomb_count_text_bundle(
-                                *v,
-                                board_assets,
-                                (size - padding) * 0.5,
-                            ));
-                        });
-                    }
-                    Tile::Empty => (),
+                            Transform::from_xyz(0., 0., 1.),
+                        ));
+                    });
+                }
+                // If the tile is a bomb neighbour we add the matching component and a text child
+                Tile::BombNeighbor(v) => {
+                    cmd.insert(BombNeighbor { count: v });
+                    cmd.with_children(|parent| {
+                        parent.spawn(Self::bomb_count_text_bundle(
+                            v,
+                            board_assets,
+                            (size - padding) * 0.5,
+                        ));
+                    });
                 }
+                Tile::Empty => (),
             }
         }
     }
@@ -1,15 +1,16 @@
+use crate::Coordinates;
 use crate::bounds::Bounds2;
-use crate::{Coordinates, TileMap};
-use bevy::{log, platform::collections::HashMap, prelude::*};
+use bevy::{platform::collections::HashMap, prelude::*};
+use minefield::Minefield;
 
 #[derive(Debug, Resource)]
 pub struct Board {
-    pub tile_map: TileMap,
+    pub minefield: Minefield,
+    pub seed: u64,
     pub bounds: Bounds2,
     pub tile_size: f32,
     pub covered_tiles: HashMap<Coordinates, Entity>,
     pub entity: Entity,
-    pub marked_tiles: Vec<Coordinates>,
 }
 
 impl Board {
@@ -27,63 +28,23 @@ impl Board {
         let coordinates = position - self.bounds.position;
         Some(Coordinates {
             x: (coordinates.x / self.tile_size) as u16,
-            y: self.tile_map.height() - 1 - (coordinates.y / self.tile_size) as u16,
+            y: self.minefield.height() - 1 - (coordinates.y / self.tile_size) as u16,
         })
     }
 
-    /// Retrieves a covered tile entity
-    pub fn tile_to_uncover(&self, coords: &Coordinates) -> Option<&Entity> {
-        if self.marked_tiles.contains(coords) {
-            None
-        } else {
-            self.covered_tiles.get(coords)
-        }
-    }
-
-    /// We try to uncover a tile, returning the entity
-    pub fn try_uncover_tile(&mut self, coords: &Coordinates) -> Option<Entity> {
-        if self.marked_tiles.contains(coords) {
-            self.unmark_tile(coords)?;
-        }
-        self.covered_tiles.remove(coords)
-    }
-
-    /// We retrieve the adjacent covered tile entities of `coord`
-    pub fn adjacent_covered_tiles(&self, coord: Coordinates) -> Vec<Entity> {
-        self.tile_map
-            .safe_square_at(coord)
-            .filter_map(|c| self.covered_tiles.get(&c))
-            .copied()
+    /// Uncovers a tile and its empty surroundings, returning the cover entities to remove
+    pub fn uncover_tile(&mut self, coords: &Coordinates) -> Vec<Entity> {
+        self.minefield
+            .uncover((*coords).into())
+            .into_iter()
+            .filter_map(|c| self.covered_tiles.remove(&Coordinates::from(c)))
             .collect()
     }
 
-    /// Removes the `coords` from `marked_tiles`
-    fn unmark_tile(&mut self, coords: &Coordinates) -> Option<Coordinates> {
-        let pos = match self.marked_tiles.iter().position(|a| a == coords) {
-            None => {
-                log::error!("Failed to unmark tile at {}", coords);
-                return None;
-            }
-            Some(p) => p,
-        };
-        Some(self.marked_tiles.remove(pos))
-    }
-
-    /// Is the board complete
-    pub fn is_completed(&self) -> bool {
-        self.tile_map.bomb_count() as usize == self.covered_tiles.len()
-    }
-
     /// We try to mark or unmark a tile, returning the entity and if the tile is marked
     pub fn try_toggle_mark(&mut self, coords: &Coordinates) -> Option<(Entity, bool)> {
         let entity = *self.covered_tiles.get(coords)?;
-        let mark = if self.marked_tiles.contains(coords) {
-            self.unmark_tile(coords)?;
-            false
-        } else {
-            self.marked_tiles.push(*coords);
-            true
-        };
+        let mark = self.minefield.toggle_flag((*coords).into())?;
         Some((entity, mark))
     }
 }
@@ -1,6 +1,3 @@
-pub(crate) mod tile;
-pub(crate) mod tile_map;
-
 pub use board::*;
 
 mod board;
@@ -0,0 +1,18 @@
+use bevy::prelude::Component;
+
+#[cfg(feature = "debug")]
+use bevy::prelude::ReflectComponent;
+#[cfg(feature = "debug")]
+use bevy_inspector_egui::prelude::ReflectInspectorOptions;
+
+/// Mesh drawing a square of tiles of a chunked board
+#[cfg_attr(
+    feature = "debug",
+    derive(bevy_inspector_egui::InspectorOptions, bevy::reflect::Reflect),
+    reflect(Component, InspectorOptions)
+)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+pub struct Chunk {
+    /// Index in `Chunks::meshes`
+    pub index: usize,
+}
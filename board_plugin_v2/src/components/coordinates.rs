@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 use std::fmt::{self, Display, Formatter};
-use std::ops::{Add, Sub};
+use std::ops::Sub;
 
 #[cfg(feature = "debug")]
 use bevy::prelude::ReflectComponent;
@@ -17,23 +17,12 @@ pub struct Coordinates {
     pub y: u16,
 }
 
-impl Add<(i8, i8)> for Coordinates {
-    type Output = Self;
-
-    fn add(self, (x, y): (i8, i8)) -> Self::Output {
-        let x = ((self.x as i16) + x as i16) as u16;
-        let y = ((self.y as i16) + y as i16) as u16;
-        Self { x, y }
-    }
-}
-
-impl Add<IVec2> for Coordinates {
-    type Output = Self;
-
-    fn add(self, IVec2 { x, y }: IVec2) -> Self::Output {
-        let x = ((self.x as i16) + x as i16) as u16;
-        let y = ((self.y as i16) + y as i16) as u16;
-        Self { x, y }
+impl Coordinates {
+    /// Applies an offset, returning `None` instead of wrapping below zero or past `u16::MAX`
+    pub fn checked_add(self, IVec2 { x, y }: IVec2) -> Option<Self> {
+        let x = u16::try_from(self.x as i32 + x).ok()?;
+        let y = u16::try_from(self.y as i32 + y).ok()?;
+        Some(Self { x, y })
     }
 }
 
@@ -47,9 +36,9 @@ impl Sub for Coordinates {
     }
 }
 
-impl Into<IVec2> for Coordinates {
-    fn into(self) -> IVec2 {
-        IVec2::new(self.x as i32, self.y as i32)
+impl From<Coordinates> for IVec2 {
+    fn from(coordinates: Coordinates) -> Self {
+        IVec2::new(coordinates.x as i32, coordinates.y as i32)
     }
 }
 
@@ -58,3 +47,15 @@ impl Display for Coordinates {
         write!(f, "({}, {})", self.x, self.y)
     }
 }
+
+impl From<minefield::Coordinates> for Coordinates {
+    fn from(minefield::Coordinates { x, y }: minefield::Coordinates) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<Coordinates> for minefield::Coordinates {
+    fn from(Coordinates { x, y }: Coordinates) -> Self {
+        Self { x, y }
+    }
+}
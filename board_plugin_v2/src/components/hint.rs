@@ -0,0 +1,15 @@
+use bevy::prelude::Component;
+
+#[cfg(feature = "debug")]
+use bevy::prelude::ReflectComponent;
+#[cfg(feature = "debug")]
+use bevy_inspector_egui::prelude::ReflectInspectorOptions;
+
+/// Layer drawn above the tile covers, holding the hint of every covered tile
+#[cfg_attr(
+    feature = "debug",
+    derive(bevy_inspector_egui::InspectorOptions, bevy::reflect::Reflect),
+    reflect(Component, InspectorOptions)
+)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+pub struct HintOverlay;
@@ -1,21 +1,35 @@
+pub use board_background::BoardBackground;
 pub use bomb::Bomb;
 pub use bomb_neighbor::BombNeighbor;
+pub use chunk::Chunk;
 pub use coordinates::Coordinates;
 pub use countdown_timer::CountdownTimer;
 pub use end_message::EndMessage;
 pub use flag::Flag;
 pub use grid::*;
+pub use hint::HintOverlay;
+pub use hud::*;
+pub use minimap::MinimapViewport;
 pub use neighbors::Neighbors;
+pub use question::Question;
 pub use tile_cover::TileCover;
+pub use tile_cursor::TileCursor;
 pub use uncover::Uncover;
 
+mod board_background;
 mod bomb;
 mod bomb_neighbor;
+mod chunk;
 mod coordinates;
 mod countdown_timer;
 mod end_message;
 mod flag;
 mod grid;
+mod hint;
+mod hud;
+mod minimap;
 mod neighbors;
+mod question;
 mod tile_cover;
+mod tile_cursor;
 mod uncover;
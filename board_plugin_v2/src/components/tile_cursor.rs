@@ -0,0 +1,19 @@
+use bevy::prelude::Component;
+
+use super::Coordinates;
+
+#[cfg(feature = "debug")]
+use bevy::prelude::ReflectComponent;
+#[cfg(feature = "debug")]
+use bevy_inspector_egui::prelude::ReflectInspectorOptions;
+
+/// Tile selected with the keyboard, hidden until the first key press
+#[cfg_attr(
+    feature = "debug",
+    derive(bevy_inspector_egui::InspectorOptions, bevy::reflect::Reflect),
+    reflect(Component, InspectorOptions)
+)]
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+pub struct TileCursor {
+    pub coordinates: Coordinates,
+}
@@ -1,6 +1,9 @@
 use bevy::{ecs::relationship::DescendantIter, prelude::*};
 
-use crate::traits::{DescendantsTraversal, HasDescendants};
+use crate::{
+    components::Coordinates,
+    traits::{DescendantsTraversal, HasDescendants},
+};
 
 #[derive(Debug, Copy, Clone, EntityEvent)]
 pub struct TileTriggerEvent(pub Entity);
@@ -8,10 +11,50 @@ pub struct TileTriggerEvent(pub Entity);
 pub struct BoardCompletedEvent;
 #[derive(Debug, Copy, Clone, EntityEvent)]
 pub struct BombExplosionEvent(pub Entity);
+/// Uncovers the unflagged neighbors of an uncovered tile once its bomb count is flagged
+#[derive(Debug, Copy, Clone, EntityEvent)]
+pub struct TileChordEvent(pub Entity);
+/// Mark put on a covered tile by the player
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum TileMark {
+    #[default]
+    None,
+    Flag,
+    /// Reminder without effect on the game, only when enabled in the board options
+    Question,
+}
+
+impl TileMark {
+    /// Mark put by the next right click, from flag to question mark, when enabled, to none
+    pub fn next(self, question_marks: bool) -> Self {
+        match self {
+            Self::None => Self::Flag,
+            Self::Flag if question_marks => Self::Question,
+            Self::Flag | Self::Question => Self::None,
+        }
+    }
+}
+
+/// Replaces the mark of a tile cover
 #[derive(Debug, Copy, Clone, EntityEvent)]
 pub struct TileMarkEvent {
     pub entity: Entity,
-    pub mark: bool,
+    pub mark: TileMark,
+}
+
+/// Move on a tile of a chunked board
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TileAction {
+    Uncover,
+    Mark(TileMark),
+    Chord,
+}
+
+/// Plays a tile of a board drawn by chunks, which has no tile entities
+#[derive(Debug, Copy, Clone, Event)]
+pub struct ChunkedTileEvent {
+    pub coordinates: Coordinates,
+    pub action: TileAction,
 }
 
 #[derive(Debug, Clone, EntityEvent)]
@@ -85,3 +128,7 @@ impl HasDescendants for CountdownEvent {
 
 #[derive(Debug, Copy, Clone, Message)]
 pub struct RestartGameEvent;
+
+/// Saves the in-progress game as the board is closed
+#[derive(Debug, Copy, Clone, Message)]
+pub struct SaveGameEvent;
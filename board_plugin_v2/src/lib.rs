@@ -4,27 +4,52 @@ pub mod resources;
 mod systems;
 mod traits;
 
-use std::time::Instant;
-
 use bevy::{
+    asset::RenderAssetUsages,
     log,
+    picking::mesh_picking::MeshPickingPlugin,
     platform::collections::{HashMap, HashSet},
     prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    time::Stopwatch,
     window::PrimaryWindow,
 };
-use rand::{rng, seq::SliceRandom};
+use minefield::{Generation, Minefield, Tile, Topology, random_seed};
 #[cfg(feature = "hierarchical_neighbors")]
 use smallvec::{SmallVec, smallvec};
+use std::time::Duration;
 
-use components::{Bomb, BombNeighbor, Coordinates, EndMessage, TileCover, Uncover};
-use events::{RestartGameEvent, TileMarkEvent};
-use resources::{Board, BoardObservers};
-use settings_plugin::resources::{BoardAssets, BoardOptions, BoardPosition, TileSize};
+use components::{
+    BoardBackground, Bomb, BombNeighbor, Coordinates, EndMessage, MineCounter, TileCover,
+    TileCursor, Uncover,
+};
+use events::{RestartGameEvent, SaveGameEvent, TileMark};
+use resources::{
+    Autoplay, Board, BoardObservers, CHUNKED_AREA, CameraDrag, Chunks, Hints, Minimap,
+    ReplayPlayback,
+};
+use settings_plugin::resources::{
+    BoardAssets, BoardGeneration, BoardOptions, BoardPosition, BoardTopology, Replay, SavedGame,
+    TileSize,
+};
 use systems::{
+    autoplay::{autoplay, stop_autoplay_on_end, toggle_autoplay},
+    camera::{clamp_camera, fit_camera, pan_camera, zoom_camera},
+    chord::chord_tiles,
+    chunk::{play_chunked_tile, rebuild_chunks, spawn_chunks},
+    cursor::{follow_cursor, keyboard_input},
     end::{on_game_end, show_message, tick_count_down},
+    hint::{toggle_hints, update_hints},
+    hud::{spawn_hud, tick_game_time, update_hud, update_mine_counter},
     input::input_handling,
     lose::uncover_tiles_on_lose,
-    mark::mark_tiles,
+    mark::{mark_tiles, spawn_mark},
+    minimap::{
+        mark_color, paint_tile, paint_uncovered_tile, spawn_minimap, update_minimap_viewport,
+    },
+    replay::play_replay,
+    save::save_game,
+    theme::reskin_board,
     uncover::{on_uncover_handler, trigger_event_handler, uncover_tiles},
     win::uncover_bombs_on_win,
 };
@@ -47,6 +72,9 @@ impl<T: ComputedStates, U: States> Plugin for BoardPluginV2<T, U> {
             OnEnter(self.running_state.clone()),
             (
                 Self::create_board,
+                spawn_chunks,
+                // Before the bombs, so that the flags of a saved game are shown on it
+                spawn_minimap,
                 Self::set_bombs,
                 #[cfg(all(feature = "simple_neighbors", feature = "hierarchical_neighbors"))]
                 Self::check_neighbors,
@@ -55,15 +83,89 @@ impl<T: ComputedStates, U: States> Plugin for BoardPluginV2<T, U> {
         )
         // We handle input and trigger events only if the state is active
         .add_systems(OnEnter(self.not_pause.clone()), Self::init_observers)
+        // The game time stops while paused
+        .add_systems(
+            Update,
+            tick_game_time.run_if(in_state(self.not_pause.clone())),
+        )
+        .add_systems(
+            Update,
+            (keyboard_input, follow_cursor)
+                .chain()
+                .before(clamp_camera)
+                .run_if(in_state(self.not_pause.clone())),
+        )
+        // Boards larger than the window are explored with the mouse
+        .add_systems(
+            Update,
+            (
+                zoom_camera,
+                pan_camera,
+                fit_camera,
+                clamp_camera,
+                update_minimap_viewport.run_if(resource_exists::<Minimap>),
+            )
+                .chain()
+                .run_if(in_state(self.running_state.clone())),
+        )
         .add_systems(OnExit(self.not_pause.clone()), Self::cleanup_observers)
+        // The auto-player is toggled while paused and plays once resumed
+        .add_systems(
+            Update,
+            toggle_autoplay.run_if(
+                in_state(self.running_state.clone())
+                    .and(not(in_state(self.not_pause.clone())))
+                    .and(not(resource_exists::<ReplayPlayback>)),
+            ),
+        )
+        .add_systems(
+            Update,
+            autoplay.run_if(in_state(self.not_pause.clone()).and(resource_exists::<Autoplay>)),
+        )
         // We handle uncovering even if the state is inactive
         .add_systems(
             Update,
-            (uncover_tiles, show_message, tick_count_down)
+            (uncover_tiles, show_message, tick_count_down, update_hud)
                 .run_if(in_state(self.running_state.clone())),
         )
-        .add_systems(OnExit(self.running_state.clone()), Self::cleanup_board);
+        // A theme changed during the game, by the settings or its file, is applied at once
+        .add_systems(
+            Update,
+            reskin_board.run_if(
+                in_state(self.running_state.clone())
+                    .and(resource_exists::<Board>)
+                    .and(resource_exists_and_changed::<BoardAssets>),
+            ),
+        )
+        .add_systems(
+            Update,
+            rebuild_chunks
+                .run_if(in_state(self.running_state.clone()).and(resource_exists::<Chunks>)),
+        )
+        .add_systems(
+            Update,
+            (toggle_hints, update_hints.run_if(resource_exists::<Hints>))
+                .chain()
+                .run_if(in_state(self.running_state.clone())),
+        )
+        .add_systems(
+            Update,
+            play_replay.run_if(
+                in_state(self.running_state.clone()).and(resource_exists::<ReplayPlayback>),
+            ),
+        )
+        // A requested save must be written before the board is gone
+        .add_systems(
+            OnExit(self.running_state.clone()),
+            (save_game, Self::cleanup_board).chain(),
+        );
+        app.init_resource::<CameraDrag>();
+        // Chunks of large boards are meshes, which aren't picked by default
+        if !app.is_plugin_added::<MeshPickingPlugin>() {
+            app.add_plugins(MeshPickingPlugin);
+        }
         app.add_message::<RestartGameEvent>();
+        app.add_message::<SaveGameEvent>();
         log::info!("Loaded Board Plugin");
     }
 }
@@ -75,24 +177,40 @@ impl<T, U> BoardPluginV2<T, U> {
         board_options: Option<Res<BoardOptions>>,
         window: Query<&Window, With<PrimaryWindow>>,
         board_assets: Res<BoardAssets>,
+        asset_server: Res<AssetServer>,
+        mut images: ResMut<Assets<Image>>,
     ) {
-        let board_assets = board_assets.clone();
+        let mut board_assets = board_assets.clone();
         let options = match board_options {
             None => BoardOptions::default(), // If no options is set we use the default one
             Some(o) => o.clone(),
         };
 
         let (width, height) = options.map_size;
+        let topology = match options.topology {
+            BoardTopology::Square => Topology::Square,
+            BoardTopology::Hexagonal => Topology::Hexagonal,
+        };
+        // Hexagonal tiles and covers are drawn with a hexagon mask tinted by the material color
+        if topology == Topology::Hexagonal {
+            let hexagon = images.add(hexagon_image());
+            board_assets.tile_material.texture = hexagon.clone();
+            board_assets.covered_tile_material.texture = hexagon;
+        }
+        let minefield = Minefield::empty(width, height)
+            .with_topology(topology)
+            .with_wrap_edges(options.wrap_edges);
+        let extent = board_extent(topology, options.map_size);
 
         let tile_size = match options.tile_size {
             TileSize::Fixed(v) => v,
             TileSize::Adaptive { min, max } => {
-                Self::adaptative_tile_size(window, (min, max), (width, height))
+                Self::adaptative_tile_size(window, (min, max), extent)
             }
         };
 
         // We deduce the size of the complete board
-        let board_size = Vec2::new(width as f32 * tile_size, height as f32 * tile_size);
+        let board_size = extent * tile_size;
         log::info!("board size: {}", board_size);
         // We define the board anchor position (bottom left)
         let board_position = match options.position {
@@ -103,20 +221,33 @@ impl<T, U> BoardPluginV2<T, U> {
         };
 
         let mut coords_map = HashMap::new();
+        // The keyboard cursor starts in the middle of the board
+        let cursor = Coordinates {
+            x: width / 2,
+            y: height / 2,
+        };
 
-        Self::spawn_tiles(
-            &mut commands,
-            options.map_size,
-            tile_size,
-            options.tile_padding,
-            &board_assets,
-            &mut coords_map,
-        );
+        // Larger boards are drawn by chunks, without tile entities
+        if width as usize * height as usize <= CHUNKED_AREA {
+            Self::spawn_tiles(
+                &mut commands,
+                topology,
+                options.map_size,
+                tile_size,
+                options.tile_padding,
+                &board_assets,
+                &mut coords_map,
+            );
+        }
 
         #[cfg(all(feature = "simple_neighbors", not(feature = "hierarchical_neighbors")))]
-        Self::assign_neighbors(&coords_map, &mut commands);
+        Self::assign_neighbors(&coords_map, &mut commands, &minefield);
 
-        #[cfg(feature = "hierarchical_neighbors")]
+        #[cfg(all(feature = "simple_neighbors", feature = "hierarchical_neighbors"))]
+        let centers =
+            Self::assign_neighbors(&coords_map, &mut commands, &minefield, options.map_size);
+
+        #[cfg(all(not(feature = "simple_neighbors"), feature = "hierarchical_neighbors"))]
         let centers = Self::assign_neighbors(&coords_map, &mut commands, options.map_size);
 
         let board_entity = commands
@@ -134,160 +265,149 @@ impl<T, U> BoardPluginV2<T, U> {
                             ..default()
                         },
                         Transform::from_xyz(board_size.x / 2., board_size.y / 2., 0.),
+                        BoardBackground,
                     )),
                     WithRelated::new(coords_map.clone().into_values()),
                     #[cfg(feature = "hierarchical_neighbors")]
                     WithRelated::new(centers),
+                    Spawn((
+                        Name::new("Tile Cursor"),
+                        Sprite {
+                            color: Color::srgba(1., 1., 0., 0.35),
+                            custom_size: Some(tile_sprite_size(topology, tile_size)),
+                            // Hexagon mask, or a plain square
+                            image: match topology {
+                                Topology::Hexagonal => board_assets.tile_material.texture.clone(),
+                                Topology::Square => default(),
+                            },
+                            ..default()
+                        },
+                        Transform::from_translation(
+                            tile_position(topology, cursor, tile_size).extend(5.),
+                        ),
+                        Visibility::Hidden,
+                        // Mouse clicks go through to the tile under the cursor
+                        Pickable::IGNORE,
+                        TileCursor {
+                            coordinates: cursor,
+                        },
+                    )),
                 )),
             ))
             .id();
 
         let observers = vec![
+            // Tiles are also triggered by replays, so this one isn't bound to the input
+            commands.add_observer(trigger_event_handler).id(),
             commands.add_observer(mark_tiles).id(),
+            commands.add_observer(chord_tiles).id(),
             commands.add_observer(on_uncover_handler).id(),
             commands.add_observer(uncover_bombs_on_win).id(),
             commands.add_observer(uncover_tiles_on_lose).id(),
             commands.add_observer(on_game_end).id(),
+            commands.add_observer(stop_autoplay_on_end).id(),
+            commands.add_observer(paint_uncovered_tile).id(),
+            commands.add_observer(play_chunked_tile).id(),
         ];
 
+        let font: Handle<Font> = asset_server.load("fonts/FiraSans-Bold.ttf");
+        let hud = spawn_hud(&mut commands, font, options.bomb_count);
+        // The game time waits for the first uncovered tile
+        let mut stopwatch = Stopwatch::new();
+        stopwatch.pause();
+
         commands.insert_resource(Board {
+            minefield,
+            seed: 0,
+            pending_bombs: false,
             tile_size,
             entity: board_entity,
             observers,
             timer: None,
             end_message: "".into(),
+            stopwatch,
+            hud,
+            metrics: None,
+            clicks: 0,
+            actions: Some(Vec::new()),
+            autoplayed: false,
             #[cfg(not(any(feature = "simple_neighbors", feature = "hierarchical_neighbors")))]
             coords_map,
         });
     }
 
-    /// Places bombs and bomb neighbor tiles
+    /// Places bombs and bomb neighbor tiles, unless they wait for the first uncovered tile
     fn set_bombs(
-        #[cfg(not(feature = "simple_neighbors"))] query: Query<(Entity, &Coordinates, &Children)>,
-        #[cfg(feature = "simple_neighbors")] query: Query<
-            (Entity, &Neighbors, &Children),
-            With<Coordinates>,
-        >,
+        query: Query<(Entity, &Coordinates, &Children)>,
         cover_query: Query<(), With<TileCover>>,
         mut commands: Commands,
         board_options: Option<Res<BoardOptions>>,
         board_assets: Res<BoardAssets>,
-        board: Res<Board>,
-        #[cfg(feature = "hierarchical_neighbors")] query_neighbors_2: Query<(
-            &GridMap,
-            &Coordinates,
-        )>,
-        #[cfg(feature = "hierarchical_neighbors")] query_neighbor_of: Query<&GridChildOf>,
+        mut board: ResMut<Board>,
+        saved_game: Option<Res<SavedGame>>,
+        replay: Option<Res<Replay>>,
+        mut chunks: Option<ResMut<Chunks>>,
+        mut counters: Query<(&MineCounter, &mut Text)>,
+        minimap: Option<Res<Minimap>>,
+        mut images: ResMut<Assets<Image>>,
     ) {
-        let mut rng = rng();
         let options = match board_options {
             None => BoardOptions::default(), // If no options is set we use the default one
             Some(o) => o.clone(),
         };
-        let bomb_count = options.bomb_count as usize;
-        let padding = options.tile_padding;
-        let size = board.tile_size;
-        #[cfg(feature = "simple_neighbors")]
-        let mut entities: Vec<(Entity, &Neighbors)> =
-            query.iter().map(|(e, n, _)| (e, n)).collect();
-        #[cfg(not(feature = "simple_neighbors"))]
-        let mut entities: Vec<(Entity, Coordinates)> =
-            query.iter().map(|(e, c, _)| (e, *c)).collect();
-        entities.shuffle(&mut rng);
-        let mut bomb_entities = HashSet::new();
-
-        for i in 0..bomb_count {
-            #[cfg(feature = "simple_neighbors")]
-            if let Some((entity, _)) = entities.get(i) {
-                commands.entity(*entity).insert(Bomb).with_child((
-                    Sprite {
-                        color: board_assets.bomb_material.color,
-                        image: board_assets.bomb_material.texture.clone(),
-                        custom_size: Some(Vec2::splat(size - padding)),
-                        ..default()
-                    },
-                    Transform::from_xyz(0., 0., 1.),
-                ));
-                bomb_entities.insert(*entity);
-            }
-            #[cfg(not(feature = "simple_neighbors"))]
-            if let Some((entity, _)) = entities.get(i) {
-                commands.entity(*entity).insert(Bomb).with_child((
-                    Sprite {
-                        color: board_assets.bomb_material.color,
-                        image: board_assets.bomb_material.texture.clone(),
-                        custom_size: Some(Vec2::splat(size - padding)),
-                        ..default()
-                    },
-                    Transform::from_xyz(0., 0., 1.),
-                ));
-                bomb_entities.insert(*entity);
-            }
-        }
-
-        let mut safe_start = None;
 
-        let start = Instant::now();
+        // A replay is played on a board generated like the recorded one
+        if let Some(replay) = replay {
+            log::info!("Playing replay with {} actions", replay.actions.len());
+            commands.insert_resource(ReplayPlayback::new(replay.clone()));
+            commands.remove_resource::<Replay>();
+            board.actions = None;
+        }
 
-        #[cfg(not(any(feature = "simple_neighbors", feature = "hierarchical_neighbors")))]
-        {
-            for (entity, coords) in entities.iter().skip(bomb_count).copied() {
-                let count = SQUARE_COORDINATES
-                    .map(|tuple| coords + tuple)
-                    .into_iter()
-                    .filter_map(|c| board.coords_map.get(&c).copied())
-                    .filter(|e| bomb_entities.contains(e))
-                    .count() as u8;
-
-                if count > 0 {
-                    commands
-                        .entity(entity)
-                        .insert(BombNeighbor { count })
-                        .with_child(Self::bomb_count_text_bundle(
-                            count,
-                            &board_assets,
-                            (size - padding) * 0.5,
-                        ));
-                } else if safe_start.is_none() {
-                    safe_start = Some(entity);
-                }
-            }
+        if let Some(saved_game) = saved_game {
+            Self::restore_game(
+                &query,
+                &cover_query,
+                &mut commands,
+                &options,
+                &board_assets,
+                &mut board,
+                &saved_game,
+                chunks.as_deref_mut(),
+                minimap
+                    .as_deref()
+                    .and_then(|minimap| images.get_mut(&minimap.image)),
+            );
+            update_mine_counter(&board, &mut counters);
+            return;
+        }
 
-            let elapsed = start.elapsed();
-            println!("hash_neighbors took {:?}", elapsed);
+        if options.first_click_safe {
+            board.pending_bombs = true;
+            return;
         }
 
-        #[cfg(feature = "simple_neighbors")]
-        {
-            for (entity, neighbors) in entities.iter().skip(bomb_count).copied() {
-                let count = neighbors
-                    .iter()
-                    .flatten()
-                    .filter(|&e| bomb_entities.contains(e))
-                    .count() as u8;
-
-                if count > 0 {
-                    commands
-                        .entity(entity)
-                        .insert(BombNeighbor { count })
-                        .with_child(Self::bomb_count_text_bundle(
-                            count,
-                            &board_assets,
-                            (size - padding) * 0.5,
-                        ));
-                } else if safe_start.is_none() {
-                    safe_start = Some(entity);
-                }
-            }
+        let generation = place_bombs(
+            &mut commands,
+            &mut board,
+            &options,
+            &board_assets,
+            query.iter().map(|(entity, &coords, _)| (entity, coords)),
+            None,
+        );
 
-            let elapsed = start.elapsed();
-            println!("simple_neighbors took {:?}", elapsed);
-        }
+        // A no guess board is only solvable from its start, so we always open it
+        let safe_start = if options.safe_start || generation == Generation::NoGuess {
+            board.minefield.safe_start()
+        } else {
+            None
+        };
 
-        #[cfg(feature = "simple_neighbors")]
-        if options.safe_start {
-            if let Some(entity) = safe_start {
-                let (_, _, children) = query.get(entity).unwrap();
+        if let (Some(chunks), Some(start)) = (chunks.as_deref_mut(), safe_start) {
+            systems::chunk::uncover(&mut board, chunks, start.into());
+        }
+        for (_, &coords, children) in query {
+            if safe_start == Some(coords.into()) {
                 for &child in children {
                     if cover_query.get(child).is_ok() {
                         commands.entity(child).insert(Uncover);
@@ -295,58 +415,100 @@ impl<T, U> BoardPluginV2<T, U> {
                 }
             }
         }
-        #[cfg(all(feature = "hierarchical_neighbors", not(feature = "simple_neighbors")))]
-        {
-            for (entity, coords) in entities.iter().skip(bomb_count).copied() {
-                let count = find_neighbors(entity, coords, &query_neighbors_2, &query_neighbor_of)
-                    .iter()
-                    .filter(|&e| bomb_entities.contains(e))
-                    .count() as u8;
-
-                if count > 0 {
-                    commands
-                        .entity(entity)
-                        .insert(BombNeighbor { count })
-                        .with_child(Self::bomb_count_text_bundle(
-                            count,
-                            &board_assets,
-                            (size - padding) * 0.5,
-                        ));
-                } else if safe_start.is_none() {
-                    safe_start = Some(entity);
-                }
-            }
+    }
 
-            let elapsed = start.elapsed();
-            println!("hierarchical_neighbors took {:?}", elapsed);
+    /// Rebuilds the bombs, uncovered tiles and flags of a saved game, which is then removed
+    fn restore_game(
+        query: &Query<(Entity, &Coordinates, &Children)>,
+        cover_query: &Query<(), With<TileCover>>,
+        commands: &mut Commands,
+        options: &BoardOptions,
+        board_assets: &BoardAssets,
+        board: &mut Board,
+        saved_game: &SavedGame,
+        chunks: Option<&mut Chunks>,
+        mut minimap: Option<&mut Image>,
+    ) {
+        log::info!("Restoring saved game with seed {}", saved_game.seed);
+        board.clicks = saved_game.clicks;
+        board.actions = saved_game.actions.clone();
+        board.seed = saved_game.seed;
+        board
+            .minefield
+            .set_bombs_at(saved_game.bombs.iter().map(|&coords| coords.into()));
+        board.metrics = Some(board.minefield.metrics());
+        board
+            .stopwatch
+            .set_elapsed(Duration::from_secs_f32(saved_game.elapsed));
+
+        spawn_tile_contents(
+            commands,
+            board,
+            options.tile_padding,
+            board_assets,
+            query.iter().map(|(entity, &coords, _)| (entity, coords)),
+        );
+
+        // The uncover handler keeps the minefield in sync, marks are restored without being recorded
+        let uncovered: HashSet<(u16, u16)> = saved_game.uncovered.iter().copied().collect();
+        let flagged: HashSet<(u16, u16)> = saved_game.flagged.iter().copied().collect();
+        let questioned: HashSet<(u16, u16)> = saved_game.questioned.iter().copied().collect();
+        // Chunked boards have no tile entities, the minefield is restored directly
+        if let Some(chunks) = chunks {
+            for &(x, y) in &uncovered {
+                board.minefield.reveal(Coordinates { x, y }.into());
+            }
+            for &(x, y) in &flagged {
+                board.minefield.set_flag(Coordinates { x, y }.into(), true);
+            }
+            chunks
+                .questions
+                .extend(questioned.iter().map(|&(x, y)| Coordinates { x, y }));
+            chunks.mark_all_dirty();
         }
-        #[cfg(not(feature = "simple_neighbors"))]
-        if options.safe_start {
-            if let Some(entity) = safe_start {
-                let (_, _, children) = query.get(entity).unwrap();
-                for &child in children {
-                    if cover_query.get(child).is_ok() {
-                        commands.entity(child).insert(Uncover);
-                    }
+        for (_, &coords, children) in query {
+            for &child in children {
+                if cover_query.get(child).is_err() {
+                    continue;
+                }
+                if uncovered.contains(&(coords.x, coords.y)) {
+                    commands.entity(child).insert(Uncover);
+                    continue;
+                }
+                let mark = if flagged.contains(&(coords.x, coords.y)) {
+                    board.minefield.set_flag(coords.into(), true);
+                    TileMark::Flag
+                } else if questioned.contains(&(coords.x, coords.y)) {
+                    TileMark::Question
+                } else {
+                    continue;
+                };
+                spawn_mark(commands, child, mark, board_assets, board.tile_size);
+                if let Some(image) = minimap.as_deref_mut() {
+                    paint_tile(board, image, coords, mark_color(mark));
                 }
             }
         }
+
+        commands.remove_resource::<SavedGame>();
+        SavedGame::delete();
     }
 
-    /// Computes a tile size that matches the window according to the tile map size
+    /// Computes a tile size that matches the window according to the tile map extent
     fn adaptative_tile_size(
         window: Query<&Window, With<PrimaryWindow>>,
-        (min, max): (f32, f32),      // Tile size constraints
-        (width, height): (u16, u16), // Tile map dimensions
+        (min, max): (f32, f32), // Tile size constraints
+        extent: Vec2,           // Tile map extent in tiles
     ) -> f32 {
         let window = window.single().unwrap();
-        let max_width = window.width() / width as f32;
-        let max_heigth = window.height() / height as f32;
+        let max_width = window.width() / extent.x;
+        let max_heigth = window.height() / extent.y;
         max_width.min(max_heigth).clamp(min, max)
     }
 
     fn spawn_tiles(
         commands: &mut Commands,
+        topology: Topology,
         (width, height): (u16, u16),
         size: f32,
         padding: f32,
@@ -356,26 +518,27 @@ impl<T, U> BoardPluginV2<T, U> {
         for y in 0..height {
             for x in 0..width {
                 let coordinates = Coordinates { x, y };
+                let sprite_size = tile_sprite_size(topology, size - padding);
                 let entity = commands
                     .spawn((
                         Name::new(format!("Tile ({}, {})", x, y)),
                         Sprite {
                             color: board_assets.tile_material.color,
-                            custom_size: Some(Vec2::splat(size - padding)),
+                            custom_size: Some(sprite_size),
                             image: board_assets.tile_material.texture.clone(),
                             ..default()
                         },
-                        Transform::from_xyz(
-                            (x as f32 * size) + (size / 2.),
-                            (y as f32 * size) + (size / 2.),
-                            1.,
+                        Transform::from_translation(
+                            tile_position(topology, coordinates, size).extend(1.),
                         ),
                         coordinates,
+                        // Uncovered tiles are picked for chording
+                        Pickable::default(),
                         // We add the cover sprites
                         children![(
                             Name::new("Tile Cover"),
                             Sprite {
-                                custom_size: Some(Vec2::splat(size - padding)),
+                                custom_size: Some(sprite_size),
                                 color: board_assets.covered_tile_material.color,
                                 image: board_assets.covered_tile_material.texture.clone(),
                                 ..default()
@@ -392,34 +555,21 @@ impl<T, U> BoardPluginV2<T, U> {
         }
     }
 
-    /// Generates the bomb counter text 2D Bundle for a given value
-    fn bomb_count_text_bundle(count: u8, board_assets: &BoardAssets, size: f32) -> impl Bundle {
-        // We retrieve the text and the correct color
-        let color = board_assets.bomb_counter_color(count);
-        // We generate a text bundle
-        (
-            Text2d::new(count.to_string()),
-            TextFont {
-                font: board_assets.bomb_counter_font.clone(),
-                font_size: size,
-                ..default()
-            },
-            TextColor(color),
-            Transform::from_xyz(0., 0., 1.),
-        )
-    }
     #[cfg(any(feature = "simple_neighbors", feature = "hierarchical_neighbors"))]
     fn assign_neighbors(
         coords_map: &HashMap<Coordinates, Entity>,
         commands: &mut Commands,
+        #[cfg(feature = "simple_neighbors")] minefield: &Minefield,
         #[cfg(feature = "hierarchical_neighbors")] (width, height): (u16, u16),
     ) -> Vec<Entity> {
         #[cfg(feature = "simple_neighbors")]
         {
             for (&coords, &entity) in coords_map {
-                let neighbors = SQUARE_COORDINATES
-                    .map(|tuple| coords + tuple)
-                    .map(|c| coords_map.get(&c).copied());
+                // Tiles with fewer neighbors leave the last slots empty
+                let mut neighbors = [None; 8];
+                for (slot, neighbor) in minefield.neighbors(coords.into()).enumerate() {
+                    neighbors[slot] = coords_map.get(&Coordinates::from(neighbor)).copied();
+                }
                 commands.entity(entity).insert(Neighbors(neighbors));
             }
         }
@@ -459,11 +609,14 @@ impl<T, U> BoardPluginV2<T, U> {
                             grid_map[0] = Some((entity, center_coords));
                         }
 
+                        // Centers of the board edges have no neighbor past them
                         let neighbors = SQUARE_COORDINATES
-                            .map(|tuple| center_coords + tuple * divisor as i32 / 3);
+                            .map(|tuple| center_coords.checked_add(tuple * divisor as i32 / 3));
 
                         for (i, coords) in neighbors.into_iter().enumerate() {
-                            if let Some(&entity) = temp.get(&coords) {
+                            if let Some(coords) = coords
+                                && let Some(&entity) = temp.get(&coords)
+                            {
                                 commands.entity(entity).insert(GridChildOf(center_entity));
                                 grid_map[i + 1] = Some((entity, coords));
                             }
@@ -487,39 +640,52 @@ impl<T, U> BoardPluginV2<T, U> {
         board: Res<Board>,
         mut commands: Commands,
         end_message: Query<Entity, With<EndMessage>>,
+        mut cameras: Query<(&mut Transform, &mut Projection), With<Camera2d>>,
+        minimap: Option<Res<Minimap>>,
     ) {
+        commands.remove_resource::<Chunks>();
+        // Gone before the tiles, which would paint it otherwise
+        if let Some(minimap) = minimap {
+            commands.entity(minimap.node).despawn();
+            commands.remove_resource::<Minimap>();
+        }
         commands.entity(board.entity).despawn();
+        commands.entity(board.hud).despawn();
         for &observer in &board.observers {
             commands.entity(observer).despawn();
         }
         commands.remove_resource::<Board>();
+        commands.remove_resource::<ReplayPlayback>();
+        commands.remove_resource::<Hints>();
+        commands.remove_resource::<Autoplay>();
 
         if let Ok(end_message_entity) = end_message.single() {
             commands.entity(end_message_entity).despawn();
         }
+        // The camera may have been moved or zoomed over the board
+        for (mut transform, mut projection) in &mut cameras {
+            transform.translation.x = 0.;
+            transform.translation.y = 0.;
+            if let Projection::Orthographic(projection) = projection.as_mut() {
+                projection.scale = 1.;
+            }
+        }
     }
 
     fn init_observers(mut commands: Commands) {
         let input_observer = commands.add_observer(input_handling).id();
-        let tile_trigger_observer = commands.add_observer(trigger_event_handler).id();
-        commands.insert_resource(BoardObservers {
-            input_observer,
-            tile_trigger_observer,
-        });
+        commands.insert_resource(BoardObservers { input_observer });
     }
 
     fn cleanup_observers(board_observers: Res<BoardObservers>, mut commands: Commands) {
-        let BoardObservers {
-            input_observer,
-            tile_trigger_observer,
-        } = *board_observers;
+        let BoardObservers { input_observer } = *board_observers;
         commands.entity(input_observer).despawn();
-        commands.entity(tile_trigger_observer).despawn();
         commands.remove_resource::<BoardObservers>();
     }
 
     #[cfg(all(feature = "simple_neighbors", feature = "hierarchical_neighbors"))]
     fn check_neighbors(
+        board: Res<Board>,
         query_neighbors: Query<(Entity, &Coordinates, &Neighbors)>,
         query_neighbors_2: Query<(&GridMap, &Coordinates)>,
         query_neighbor_of: Query<&GridChildOf>,
@@ -527,10 +693,15 @@ impl<T, U> BoardPluginV2<T, U> {
     ) {
         for (entity, &coords, neighbors) in query_neighbors {
             let neighbors: HashSet<Entity> = neighbors.iter().flatten().copied().collect();
-            let neighbors_2: HashSet<Entity> =
-                find_neighbors(entity, coords, &query_neighbors_2, &query_neighbor_of)
-                    .into_iter()
-                    .collect();
+            let neighbors_2: HashSet<Entity> = find_neighbors(
+                entity,
+                coords,
+                &board.minefield,
+                &query_neighbors_2,
+                &query_neighbor_of,
+            )
+            .into_iter()
+            .collect();
 
             if neighbors != neighbors_2 {
                 println!("--{}--", coords);
@@ -553,13 +724,299 @@ impl<T, U> BoardPluginV2<T, U> {
         }
     }
 }
+
+/// Generates the bomb layout, keeping `start` and its neighbors safe when given, and spawns the
+/// bomb and bomb counter children of the `tiles`.
+///
+/// Returns the generation mode that was used
+pub(crate) fn place_bombs(
+    commands: &mut Commands,
+    board: &mut Board,
+    options: &BoardOptions,
+    board_assets: &BoardAssets,
+    tiles: impl IntoIterator<Item = (Entity, Coordinates)>,
+    start: Option<Coordinates>,
+) -> Generation {
+    let seed = options.seed.unwrap_or_else(random_seed);
+    log::info!("Board seed: {}", seed);
+    board.seed = seed;
+    board.pending_bombs = false;
+
+    let generation = match options.generation {
+        BoardGeneration::Random => Generation::Random,
+        BoardGeneration::NoGuess => Generation::NoGuess,
+    };
+    match board
+        .minefield
+        .generate(options.bomb_count, seed, generation, start.map(Into::into))
+    {
+        Ok(report) => {
+            log::info!("Bombs placed after {} attempt(s)", report.attempts);
+            if generation == Generation::NoGuess && !report.solvable {
+                log::warn!("No solvable layout found, the board may require guessing");
+            }
+        }
+        // Options from an edited save or replay file may not have been validated
+        Err(e) => log::error!("No bombs placed: {}", e),
+    }
+    #[cfg(feature = "debug")]
+    // Minefield debugging
+    log::info!("{}", board.minefield.console_output());
+
+    let metrics = board.minefield.metrics();
+    log::info!(
+        "3BV: {}, {} opening(s), {} island(s)",
+        metrics.bbbv,
+        metrics.openings,
+        metrics.islands
+    );
+    board.metrics = Some(metrics);
+
+    spawn_tile_contents(commands, board, options.tile_padding, board_assets, tiles);
+
+    generation
+}
+
+/// Spawns the bomb and bomb counter children of the `tiles` from the minefield
+fn spawn_tile_contents(
+    commands: &mut Commands,
+    board: &Board,
+    padding: f32,
+    board_assets: &BoardAssets,
+    tiles: impl IntoIterator<Item = (Entity, Coordinates)>,
+) {
+    let size = board.tile_size;
+
+    for (entity, coords) in tiles {
+        match board.minefield.tile_at(coords.into()) {
+            Some(Tile::Bomb) => {
+                commands.entity(entity).insert(Bomb).with_child((
+                    Sprite {
+                        color: board_assets.bomb_material.color,
+                        image: board_assets.bomb_material.texture.clone(),
+                        custom_size: Some(Vec2::splat(size - padding)),
+                        ..default()
+                    },
+                    Transform::from_xyz(0., 0., 1.),
+                ));
+            }
+            Some(Tile::BombNeighbor(count)) => {
+                commands
+                    .entity(entity)
+                    .insert(BombNeighbor { count })
+                    .with_child(bomb_count_text_bundle(
+                        count,
+                        board_assets,
+                        (size - padding) * 0.5,
+                    ));
+            }
+            Some(Tile::Empty) | None => (),
+        }
+    }
+}
+
+/// Size in tiles of a board of `width` by `height` tiles
+fn board_extent(topology: Topology, (width, height): (u16, u16)) -> Vec2 {
+    match topology {
+        Topology::Square => Vec2::new(width as f32, height as f32),
+        // Hexagon rows overlap and odd rows stick out by half a tile
+        Topology::Hexagonal => Vec2::new(
+            width as f32 + 0.5,
+            (height as f32 - 1.) * HEX_ROW_HEIGHT + HEX_HEIGHT,
+        ),
+    }
+}
+
+/// Center of the tile at `coordinates`, relative to the bottom left of the board
+pub fn tile_position(topology: Topology, coordinates: Coordinates, size: f32) -> Vec2 {
+    let (x, y) = (coordinates.x as f32, coordinates.y as f32);
+    match topology {
+        Topology::Square => Vec2::new(x * size + size / 2., y * size + size / 2.),
+        Topology::Hexagonal => {
+            let shift = if coordinates.y.is_multiple_of(2) {
+                0.
+            } else {
+                0.5
+            };
+            Vec2::new(
+                (x + shift) * size + size / 2.,
+                y * HEX_ROW_HEIGHT * size + HEX_HEIGHT * size / 2.,
+            )
+        }
+    }
+}
+
+/// Tile with the nearest center to `position`, relative to the bottom left of the board
+pub fn tile_at_position(
+    topology: Topology,
+    (width, height): (u16, u16),
+    position: Vec2,
+    size: f32,
+) -> Option<Coordinates> {
+    let (row, rows) = match topology {
+        Topology::Square => ((position.y / size - 0.5).round() as i32, 0),
+        // Hexagons stick out into the rows above and below theirs
+        Topology::Hexagonal => (
+            ((position.y - HEX_HEIGHT * size / 2.) / (HEX_ROW_HEIGHT * size)).round() as i32,
+            1,
+        ),
+    };
+    (row - rows..=row + rows)
+        .filter(|&y| (0..height as i32).contains(&y))
+        .filter_map(|y| {
+            let shift = match topology {
+                Topology::Hexagonal if y % 2 == 1 => 0.5,
+                _ => 0.,
+            };
+            let x = (position.x / size - shift - 0.5).round() as i32;
+            (0..width as i32).contains(&x).then_some(Coordinates {
+                x: x as u16,
+                y: y as u16,
+            })
+        })
+        .min_by(|&a, &b| {
+            let distance = |c| tile_position(topology, c, size).distance_squared(position);
+            distance(a).total_cmp(&distance(b))
+        })
+}
+
+/// Size of the tile sprites for tiles `size` wide
+fn tile_sprite_size(topology: Topology, size: f32) -> Vec2 {
+    match topology {
+        Topology::Square => Vec2::splat(size),
+        Topology::Hexagonal => Vec2::new(size, size * HEX_HEIGHT),
+    }
+}
+
+/// Height of a pointy-top hexagon one tile wide
+const HEX_HEIGHT: f32 = 2. / SQRT_3;
+/// Vertical distance between two hexagon rows
+const HEX_ROW_HEIGHT: f32 = SQRT_3 / 2.;
+const SQRT_3: f32 = 1.732_050_8;
+/// Width of the generated hexagon texture in pixels
+const HEX_IMAGE_WIDTH: u32 = 64;
+
+/// Is the pixel inside the pointy-top hexagon filling an image of `width` by `height` pixels
+fn in_hexagon(x: u32, y: u32, width: u32, height: u32) -> bool {
+    let radius = height as f32 / 2.;
+    let half_width = width as f32 / 2.;
+    // Distances from the center to the pixel center
+    let dx = (x as f32 + 0.5 - half_width).abs();
+    let dy = (y as f32 + 0.5 - radius).abs();
+    dx <= half_width && dy <= radius - dx / SQRT_3
+}
+
+/// White pointy-top hexagon on a transparent background, tinted by the sprite color
+fn hexagon_image() -> Image {
+    let width = HEX_IMAGE_WIDTH;
+    let height = (width as f32 * HEX_HEIGHT).round() as u32;
+
+    let mut data = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let inside = in_hexagon(x, y, width, height);
+            data.extend_from_slice(if inside { &[255; 4] } else { &[0; 4] });
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        // Kept in the main world for the alpha picking
+        RenderAssetUsages::default(),
+    )
+}
+
+/// Generates the bomb counter text 2D Bundle for a given value
+fn bomb_count_text_bundle(count: u8, board_assets: &BoardAssets, size: f32) -> impl Bundle {
+    // We retrieve the text and the correct color
+    let color = board_assets.bomb_counter_color(count);
+    // We generate a text bundle
+    (
+        Text2d::new(count.to_string()),
+        TextFont {
+            font: board_assets.bomb_counter_font.clone(),
+            font_size: size,
+            ..default()
+        },
+        TextColor(color),
+        Transform::from_xyz(0., 0., 1.),
+    )
+}
+
+/// Neighbors of the tile `entity` at `coords` in the `minefield`, looked up in the square grid
+/// hierarchy
 #[cfg(feature = "hierarchical_neighbors")]
 pub fn find_neighbors(
     entity: Entity,
     coords: Coordinates,
+    minefield: &Minefield,
     query_neighbors: &Query<(&GridMap, &Coordinates)>,
     query_neighbor_of: &Query<&GridChildOf>,
 ) -> SmallVec<[Entity; 8]> {
+    let found = find_square_neighbors(entity, coords, query_neighbors, query_neighbor_of);
+    minefield
+        .neighbors(coords.into())
+        .map(Coordinates::from)
+        .filter_map(|neighbor| {
+            found
+                .iter()
+                .find(|&&(_, c)| c == neighbor)
+                .map(|&(neighbor_entity, _)| neighbor_entity)
+                // Tiles across a wrapped edge are out of reach of the square lookup
+                .or_else(|| find_tile(entity, neighbor, query_neighbors, query_neighbor_of))
+        })
+        .collect()
+}
+
+/// Tile at `coords`, found by climbing the grid hierarchy from `entity` up to its root and
+/// going down through the centers closest to `coords`
+#[cfg(feature = "hierarchical_neighbors")]
+fn find_tile(
+    entity: Entity,
+    coords: Coordinates,
+    query_neighbors: &Query<(&GridMap, &Coordinates)>,
+    query_neighbor_of: &Query<&GridChildOf>,
+) -> Option<Entity> {
+    let mut current = entity;
+    while let Ok(child_of) = query_neighbor_of.get(current) {
+        current = child_of.0;
+    }
+
+    // Each center covers the square area around it, so the closest one holds the tile
+    let distance = |other: Coordinates| {
+        let offset = other - coords;
+        offset.x.abs().max(offset.y.abs())
+    };
+    let mut closest = None;
+    while let Ok((grid_map, _)) = query_neighbors.get(current) {
+        let &(child_entity, child_coords) = grid_map
+            .iter()
+            .flatten()
+            .min_by_key(|&&(_, child_coords)| distance(child_coords))?;
+        current = child_entity;
+        closest = Some((child_entity, child_coords));
+    }
+
+    // Tiles are the only grid entries without a grid map of their own
+    closest
+        .filter(|&(_, tile_coords)| tile_coords == coords)
+        .map(|(tile_entity, _)| tile_entity)
+}
+
+#[cfg(feature = "hierarchical_neighbors")]
+fn find_square_neighbors(
+    entity: Entity,
+    coords: Coordinates,
+    query_neighbors: &Query<(&GridMap, &Coordinates)>,
+    query_neighbor_of: &Query<&GridChildOf>,
+) -> SmallVec<[(Entity, Coordinates); 8]> {
     let Ok(child_of) = query_neighbor_of.get(entity) else {
         return smallvec![];
     };
@@ -628,7 +1085,10 @@ pub fn find_neighbors(
     let bounds = IRect::from_center_size(coords_l2.into(), IVec2::splat(9));
 
     for (tuple, indexes) in index_map {
-        let coords_l1 = coords_l1 + tuple;
+        let Some(coords_l1) = coords_l1.checked_add(IVec2::new(tuple.0.into(), tuple.1.into()))
+        else {
+            continue;
+        };
 
         if bounds.contains((coords_l1).into()) {
             if let Some(i) = offset_to_index(coords_l1 - coords_l2) {
@@ -741,7 +1201,7 @@ fn find_intersecting(
 
 #[cfg(feature = "hierarchical_neighbors")]
 fn find_coordinates(
-    found: &mut SmallVec<[Entity; 8]>,
+    found: &mut SmallVec<[(Entity, Coordinates); 8]>,
     coords_l1: Coordinates,
     indexes: &[usize],
     center_entity: Entity,
@@ -816,21 +1276,27 @@ pub const SQUARE_COORDINATES: [IVec2; 8] = [
     IVec2::new(1, 1),
 ];
 
+#[cfg(feature = "hierarchical_neighbors")]
 fn intersects(a: IRect, b: IRect) -> bool {
     !(a.max.x < b.min.x || b.max.x < a.min.x || a.max.y < b.min.y || b.max.y < a.min.y)
 }
 
 #[inline]
 #[cfg(feature = "hierarchical_neighbors")]
-fn add_neighbors(found: &mut SmallVec<[Entity; 8]>, grid_map: &GridMap, indexes: &[usize]) {
+fn add_neighbors(
+    found: &mut SmallVec<[(Entity, Coordinates); 8]>,
+    grid_map: &GridMap,
+    indexes: &[usize],
+) {
     for &i in indexes {
-        if let Some((e, _)) = grid_map[i] {
-            found.push(e);
+        if let Some(neighbor) = grid_map[i] {
+            found.push(neighbor);
         }
     }
 }
 
 #[inline]
+#[cfg(feature = "hierarchical_neighbors")]
 fn offset_to_index(offset: IVec2) -> Option<usize> {
     match (offset.x, offset.y) {
         (-3, -3) => Some(1),
@@ -846,6 +1312,7 @@ fn offset_to_index(offset: IVec2) -> Option<usize> {
     }
 }
 #[inline]
+#[cfg(feature = "hierarchical_neighbors")]
 fn get_size(level: u8) -> IVec2 {
     IVec2::splat(3_i32.pow(level as u32))
 }
@@ -0,0 +1,27 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+/// Delay between two moves of the auto-player, changed with the + and - keys
+pub const DEFAULT_AUTOPLAY_DELAY: Duration = Duration::from_millis(250);
+
+/// Auto-player making the moves instead of the player, removed when the game ends
+#[derive(Debug, Resource)]
+pub struct Autoplay {
+    /// Ticks once per move
+    pub timer: Timer,
+}
+
+impl Autoplay {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            timer: Timer::new(delay, TimerMode::Repeating),
+        }
+    }
+}
+
+impl Default for Autoplay {
+    fn default() -> Self {
+        Self::new(DEFAULT_AUTOPLAY_DELAY)
+    }
+}
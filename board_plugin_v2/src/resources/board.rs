@@ -1,17 +1,144 @@
 #[cfg(not(any(feature = "simple_neighbors", feature = "hierarchical_neighbors")))]
 use bevy::platform::collections::HashMap;
-use bevy::prelude::*;
+use bevy::{log, prelude::*, time::Stopwatch};
+use minefield::{BoardMetrics, GameState, Minefield, Tile, TileState};
+use settings_plugin::resources::{ReplayAction, ReplayActionKind};
 
-#[cfg(not(any(feature = "simple_neighbors", feature = "hierarchical_neighbors")))]
-use crate::components::Coordinates;
+use crate::{components::Coordinates, events::TileMark};
 
 #[derive(Debug, Resource)]
 pub struct Board {
+    pub minefield: Minefield,
+    /// Seed of the bomb layout, set once the bombs are placed
+    pub seed: u64,
+    /// Bombs wait for the first uncovered tile to be placed around it
+    pub pending_bombs: bool,
     pub tile_size: f32,
     pub entity: Entity,
     pub observers: Vec<Entity>,
     pub timer: Option<Timer>,
     pub end_message: String,
+    /// Difficulty of the bomb layout, known once the bombs are placed
+    pub metrics: Option<BoardMetrics>,
+    /// Uncover, flag and chord clicks made in this game
+    pub clicks: u32,
+    /// Game time, running from the first uncovered tile until the game ends
+    pub stopwatch: Stopwatch,
+    pub hud: Entity,
+    /// Actions recorded for the replay, `None` when the game isn't recorded
+    pub actions: Option<Vec<ReplayAction>>,
+    /// The auto-player made moves in this game
+    pub autoplayed: bool,
     #[cfg(not(any(feature = "simple_neighbors", feature = "hierarchical_neighbors")))]
     pub coords_map: HashMap<Coordinates, Entity>,
 }
+
+impl Board {
+    /// Records a player action at the current game time
+    pub fn record(&mut self, coordinates: Coordinates, kind: ReplayActionKind) {
+        self.clicks += 1;
+        let time = self.stopwatch.elapsed_secs();
+        if let Some(actions) = &mut self.actions {
+            actions.push(ReplayAction {
+                time,
+                coordinates: (coordinates.x, coordinates.y),
+                kind,
+            });
+        }
+    }
+
+    /// Applies the rules of an uncover click on the tile at `coords`: records it and starts the
+    /// game time. Returns `false` when the tile can't be uncovered, the tiles being uncovered by
+    /// the caller otherwise
+    pub fn play_uncover(&mut self, coords: Coordinates) -> bool {
+        // Flagged tiles can't be uncovered
+        if self.minefield.state() != GameState::Playing
+            || self.minefield.state_at(coords.into()) != Some(TileState::Covered)
+        {
+            return false;
+        }
+        self.record(coords, ReplayActionKind::Uncover);
+        // The game time starts with the first uncovered tile
+        if self.stopwatch.is_paused() {
+            self.stopwatch.unpause();
+        }
+        true
+    }
+
+    /// Applies the rules of replacing the `current` mark of the tile at `coords` by `mark`,
+    /// keeping the minefield flags in sync. Returns `false` when the mark doesn't change
+    pub fn play_mark(&mut self, coords: Coordinates, current: TileMark, mark: TileMark) -> bool {
+        if current == mark
+            || self.minefield.state() != GameState::Playing
+            || self.minefield.state_at(coords.into()) == Some(TileState::Uncovered)
+        {
+            return false;
+        }
+        self.minefield
+            .set_flag(coords.into(), mark == TileMark::Flag);
+        self.record(coords, mark_action(current, mark));
+        true
+    }
+
+    /// Applies the rules of a chord click on the tile at `coords`, returning the covered and
+    /// unflagged neighbors to uncover when it has as many flags around as bombs
+    pub fn play_chord(&mut self, coords: Coordinates) -> Option<Vec<Coordinates>> {
+        // Only uncovered bomb counters can be chorded
+        let Some(Tile::BombNeighbor(count)) = self.minefield.tile_at(coords.into()) else {
+            return None;
+        };
+        if self.minefield.state() != GameState::Playing
+            || self.minefield.state_at(coords.into()) != Some(TileState::Uncovered)
+        {
+            return None;
+        }
+        self.record(coords, ReplayActionKind::Chord);
+
+        let state_of = |c| self.minefield.state_at(c);
+        let flag_count = self
+            .minefield
+            .neighbors(coords.into())
+            .filter(|&c| state_of(c) == Some(TileState::Flagged))
+            .count();
+        if flag_count != count as usize {
+            log::info!(
+                "Can't chord tile on {}: {} flag(s) for {} bomb(s)",
+                coords,
+                flag_count,
+                count
+            );
+            return None;
+        }
+        // A wrong flag uncovers a bomb, which goes through the usual explosion
+        Some(
+            self.minefield
+                .neighbors(coords.into())
+                .filter(|&c| state_of(c) == Some(TileState::Covered))
+                .map(Coordinates::from)
+                .collect(),
+        )
+    }
+
+    /// 3BV cleared per second, for a won game
+    pub fn bbbv_per_second(&self) -> Option<f32> {
+        let time = self.stopwatch.elapsed_secs();
+        let metrics = self.metrics?;
+        (time > 0.).then(|| metrics.bbbv as f32 / time)
+    }
+
+    /// 3BV over the clicks made, 1 being a perfect game
+    pub fn efficiency(&self) -> Option<f32> {
+        let metrics = self.metrics?;
+        (self.clicks > 0).then(|| metrics.bbbv as f32 / self.clicks as f32)
+    }
+}
+
+/// Replay action replacing the `current` mark of a tile by `mark`
+fn mark_action(current: TileMark, mark: TileMark) -> ReplayActionKind {
+    match (current, mark) {
+        (_, TileMark::Flag) => ReplayActionKind::Mark(true),
+        (_, TileMark::Question) => ReplayActionKind::QuestionMark(true),
+        (TileMark::Flag, TileMark::None) => ReplayActionKind::Mark(false),
+        _ => ReplayActionKind::QuestionMark(false),
+    }
+}
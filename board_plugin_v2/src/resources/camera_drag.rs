@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+
+/// Distance in pixels the mouse moves with the left button held before the camera pans
+pub const DRAG_THRESHOLD: f32 = 5.;
+
+/// Mouse drag panning the camera, reset when the left button is released
+#[derive(Debug, Default, Resource)]
+pub struct CameraDrag {
+    /// Cursor position of the previous frame, while the left button is held
+    pub last_position: Option<Vec2>,
+    /// Distance the cursor moved since the left button was pressed
+    pub distance: f32,
+}
+
+impl CameraDrag {
+    /// Is the mouse panning the camera instead of clicking a tile
+    pub fn is_panning(&self) -> bool {
+        self.distance > DRAG_THRESHOLD
+    }
+}
@@ -0,0 +1,85 @@
+use bevy::{platform::collections::HashSet, prelude::*};
+use minefield::{Minefield, TileState};
+
+use crate::{components::Coordinates, events::TileMark};
+
+/// Side in tiles of the square chunks
+pub const CHUNK_SIZE: u16 = 32;
+/// Boards with more tiles are drawn by chunks rather than with entities for every tile
+pub const CHUNKED_AREA: usize = 25_600;
+
+/// Meshes of a board drawn by chunks, the game itself being kept by the minefield
+#[derive(Debug, Resource)]
+pub struct Chunks {
+    /// Chunk meshes, row by row from the bottom left of the board
+    pub meshes: Vec<Handle<Mesh>>,
+    /// Size of the board in tiles
+    pub map_size: (u16, u16),
+    /// Chunks per row
+    pub columns: u16,
+    /// Chunks showing outdated tiles, rebuilt on the next frame
+    pub dirty: Vec<bool>,
+    /// Question marked tiles, which the minefield doesn't know about
+    pub questions: HashSet<Coordinates>,
+}
+
+impl Chunks {
+    pub fn new(map_size: (u16, u16)) -> Self {
+        let columns = map_size.0.div_ceil(CHUNK_SIZE);
+        let count = columns as usize * map_size.1.div_ceil(CHUNK_SIZE) as usize;
+        Self {
+            meshes: Vec::with_capacity(count),
+            map_size,
+            columns,
+            dirty: vec![false; count],
+            questions: HashSet::new(),
+        }
+    }
+
+    /// Number of chunks of the board
+    pub fn len(&self) -> usize {
+        self.dirty.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dirty.is_empty()
+    }
+
+    /// Index of the chunk holding the tile at `coordinates`
+    pub fn index(&self, coordinates: Coordinates) -> usize {
+        (coordinates.y / CHUNK_SIZE) as usize * self.columns as usize
+            + (coordinates.x / CHUNK_SIZE) as usize
+    }
+
+    /// Tiles of the chunk at `index`, row by row
+    pub fn tiles(&self, index: usize) -> impl Iterator<Item = Coordinates> + use<> {
+        let (width, height) = self.map_size;
+        let x0 = (index % self.columns as usize) as u16 * CHUNK_SIZE;
+        let y0 = (index / self.columns as usize) as u16 * CHUNK_SIZE;
+        let x1 = x0.saturating_add(CHUNK_SIZE).min(width);
+        let y1 = y0.saturating_add(CHUNK_SIZE).min(height);
+        (y0..y1).flat_map(move |y| (x0..x1).map(move |x| Coordinates { x, y }))
+    }
+
+    /// Schedules the rebuild of the chunk holding the tile at `coordinates`
+    pub fn mark_dirty(&mut self, coordinates: Coordinates) {
+        let index = self.index(coordinates);
+        self.dirty[index] = true;
+    }
+
+    /// Schedules the rebuild of every chunk, when the game ends
+    pub fn mark_all_dirty(&mut self) {
+        self.dirty.fill(true);
+    }
+
+    /// Current mark of the tile at `coordinates`
+    pub fn mark_at(&self, minefield: &Minefield, coordinates: Coordinates) -> TileMark {
+        if minefield.state_at(coordinates.into()) == Some(TileState::Flagged) {
+            TileMark::Flag
+        } else if self.questions.contains(&coordinates) {
+            TileMark::Question
+        } else {
+            TileMark::None
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use bevy::prelude::*;
+
+use super::CHUNKED_AREA;
+
+/// Largest board the hints and the auto-player are offered on: both compute the bomb
+/// probabilities on every move, and the overlay has a sprite per covered tile
+pub const HINTS_MAX_AREA: usize = CHUNKED_AREA;
+
+/// Shown hint overlay, removed when the hints are hidden
+#[derive(Debug, Resource)]
+pub struct Hints {
+    /// The `HintOverlay` entity
+    pub overlay: Entity,
+    /// Minefield revision the overlay was drawn for
+    pub revision: Option<u32>,
+}
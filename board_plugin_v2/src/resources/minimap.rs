@@ -0,0 +1,10 @@
+use bevy::prelude::*;
+
+/// Minimap of a board larger than the window, one pixel per tile
+#[derive(Debug, Resource)]
+pub struct Minimap {
+    /// The UI node showing the minimap
+    pub node: Entity,
+    /// Tile states, painted as they change
+    pub image: Handle<Image>,
+}
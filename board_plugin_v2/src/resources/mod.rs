@@ -1,5 +1,17 @@
+pub use autoplay::*;
 pub use board::*;
 pub use board_observers::*;
+pub use camera_drag::*;
+pub use chunks::*;
+pub use hints::*;
+pub use minimap::*;
+pub use replay_playback::*;
 
+mod autoplay;
 mod board;
 mod board_observers;
+mod camera_drag;
+mod chunks;
+mod hints;
+mod minimap;
+mod replay_playback;
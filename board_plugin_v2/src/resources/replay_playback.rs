@@ -0,0 +1,25 @@
+use bevy::prelude::*;
+use settings_plugin::resources::Replay;
+
+/// Replay played back on the board instead of player input
+#[derive(Debug, Resource)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    /// Replay time in seconds
+    pub clock: f32,
+    /// Index of the next action to apply
+    pub next: usize,
+    /// Playback speed multiplier
+    pub speed: f32,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            clock: 0.0,
+            next: 0,
+            speed: 1.0,
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use bevy::{log, prelude::*};
+use minefield::GameState;
+
+use crate::{
+    components::{Coordinates, TileCover, Uncover},
+    events::{GameEndEvent, TileMark, TileMarkEvent, TileTriggerEvent},
+    resources::{Autoplay, Board, HINTS_MAX_AREA},
+};
+
+/// Delay limits, changed with the + and - keys
+const MIN_DELAY: f32 = 0.01;
+const MAX_DELAY: f32 = 4.0;
+
+/// Turns the auto-player on or off with the P key, while the game is paused
+pub fn toggle_autoplay(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    board: Res<Board>,
+    autoplay: Option<Res<Autoplay>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+    if autoplay.is_some() {
+        log::info!("Autoplay off");
+        commands.remove_resource::<Autoplay>();
+    } else if board.minefield.area() > HINTS_MAX_AREA {
+        log::warn!(
+            "Autoplay is only offered on boards up to {} tiles",
+            HINTS_MAX_AREA
+        );
+    } else {
+        log::info!("Autoplay on, resume the game to start it");
+        commands.insert_resource(Autoplay::default());
+    }
+}
+
+/// Makes one move per delay: uncovers a certainly safe tile, flags a certain bomb or else
+/// uncovers the tile with the lowest bomb probability
+pub fn autoplay(
+    mut commands: Commands,
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut autoplay: ResMut<Autoplay>,
+    mut board: ResMut<Board>,
+    tiles: Query<(&Coordinates, &Children)>,
+    covers: Query<(), With<TileCover>>,
+    uncovering: Query<(), With<Uncover>>,
+) {
+    let delay = autoplay.timer.duration().as_secs_f32();
+    if keys.just_pressed(KeyCode::Equal) {
+        let delay = (delay / 2.0).max(MIN_DELAY);
+        autoplay.timer.set_duration(Duration::from_secs_f32(delay));
+        log::info!("Autoplay delay {:.2}s", delay);
+    }
+    if keys.just_pressed(KeyCode::Minus) {
+        let delay = (delay * 2.0).min(MAX_DELAY);
+        autoplay.timer.set_duration(Duration::from_secs_f32(delay));
+        log::info!("Autoplay delay {:.2}s", delay);
+    }
+
+    if !autoplay.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    // Uncovering spreads over several frames, the next move waits for it to settle
+    if board.minefield.state() != GameState::Playing || !uncovering.is_empty() {
+        return;
+    }
+
+    let probabilities = board.minefield.probabilities();
+    let Some((best, probability)) = probabilities.best() else {
+        return;
+    };
+    let certain_bomb = probabilities
+        .iter()
+        .find(|&(_, p)| p >= 1.)
+        .map(|(coords, _)| coords);
+    let (target, mark) = match certain_bomb {
+        Some(bomb) if probability > 0. => (bomb, true),
+        _ => (best, false),
+    };
+    if !mark && probability > 0. {
+        log::info!("Autoplay guessing {} ({:.0}%)", target, probability * 100.);
+    }
+
+    let target = Coordinates::from(target);
+    let Some(cover) = tiles
+        .iter()
+        .find(|&(&coords, _)| coords == target)
+        .and_then(|(_, children)| children.iter().find(|&child| covers.contains(child)))
+    else {
+        log::error!("No covered tile on {}", target);
+        return;
+    };
+    // Games played by the auto-player don't count in the statistics
+    board.autoplayed = true;
+    if mark {
+        commands.trigger(TileMarkEvent {
+            entity: cover,
+            mark: TileMark::Flag,
+        });
+    } else {
+        commands.trigger(TileTriggerEvent(cover));
+    }
+}
+
+/// The auto-player stops with the game
+pub fn stop_autoplay_on_end(_event: On<GameEndEvent>, mut commands: Commands) {
+    commands.remove_resource::<Autoplay>();
+}
@@ -0,0 +1,152 @@
+use bevy::{
+    input::mouse::{AccumulatedMouseScroll, MouseScrollUnit},
+    prelude::*,
+    window::PrimaryWindow,
+};
+
+use crate::{
+    board_extent,
+    resources::{Board, CameraDrag},
+};
+
+/// Smallest projection scale, tiles being at most four times bigger than without zoom
+const MIN_SCALE: f32 = 0.25;
+/// Scale factor of one mouse wheel notch
+const ZOOM_STEP: f32 = 1.1;
+/// Scrolled pixels of touchpads making up a mouse wheel notch
+const PIXELS_PER_NOTCH: f32 = 50.;
+
+/// Zooms the camera with the mouse wheel, keeping the point under the mouse cursor in place
+pub fn zoom_camera(
+    scroll: Res<AccumulatedMouseScroll>,
+    board: Res<Board>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform, &mut Transform, &mut Projection), With<Camera2d>>,
+) {
+    if scroll.delta.y == 0. {
+        return;
+    }
+    let (camera, global_transform, mut transform, mut projection) = camera.into_inner();
+    let Projection::Orthographic(projection) = projection.as_mut() else {
+        return;
+    };
+    let notches = match scroll.unit {
+        MouseScrollUnit::Line => scroll.delta.y,
+        MouseScrollUnit::Pixel => scroll.delta.y / PIXELS_PER_NOTCH,
+    };
+    // Zooming out stops once the whole board is shown
+    let max_scale = fit_scale(&board, camera).max(1.);
+    let scale = (projection.scale * ZOOM_STEP.powf(-notches)).clamp(MIN_SCALE, max_scale);
+
+    if let Some(anchor) = window
+        .cursor_position()
+        .and_then(|position| camera.viewport_to_world_2d(global_transform, position).ok())
+    {
+        let offset = transform.translation.truncate() - anchor;
+        let z = transform.translation.z;
+        transform.translation = (anchor + offset * scale / projection.scale).extend(z);
+    }
+    projection.scale = scale;
+}
+
+/// Pans the camera while the mouse is dragged with the left button, the release then doesn't
+/// uncover the tile under it
+pub fn pan_camera(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mut drag: ResMut<CameraDrag>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&mut Transform, &Projection), With<Camera2d>>,
+) {
+    if !mouse_input.pressed(MouseButton::Left) {
+        // Tile clicks are handled before, they still know that the mouse was panning
+        if drag.last_position.is_some() {
+            *drag = CameraDrag::default();
+        }
+        return;
+    }
+    let Some(position) = window.cursor_position() else {
+        return;
+    };
+    let (mut transform, projection) = camera.into_inner();
+    if let Some(last_position) = drag.last_position {
+        let delta = position - last_position;
+        drag.distance += delta.length();
+        if drag.is_panning() {
+            let scale = match projection {
+                Projection::Orthographic(projection) => projection.scale,
+                _ => 1.,
+            };
+            // The window y axis points down, the world one up
+            transform.translation.x -= delta.x * scale;
+            transform.translation.y += delta.y * scale;
+        }
+    }
+    drag.last_position = Some(position);
+}
+
+/// Zooms and centers the camera to show the whole board with the 0 or Home key
+pub fn fit_camera(
+    keys: Res<ButtonInput<KeyCode>>,
+    board: Res<Board>,
+    boards: Query<&Transform, Without<Camera2d>>,
+    camera: Single<(&Camera, &mut Transform, &mut Projection), With<Camera2d>>,
+) {
+    if !keys.any_just_pressed([KeyCode::Digit0, KeyCode::Numpad0, KeyCode::Home]) {
+        return;
+    }
+    let Some(bounds) = board_bounds(&board, &boards) else {
+        return;
+    };
+    let (camera, mut transform, mut projection) = camera.into_inner();
+    if let Projection::Orthographic(projection) = projection.as_mut() {
+        projection.scale = fit_scale(&board, camera).max(MIN_SCALE);
+    }
+    let z = transform.translation.z;
+    transform.translation = bounds.center().extend(z);
+}
+
+/// Keeps the whole board in view when it fits, and the view over the board otherwise
+pub fn clamp_camera(
+    board: Res<Board>,
+    boards: Query<&Transform, Without<Camera2d>>,
+    camera: Single<(&Camera, &mut Transform, &Projection), With<Camera2d>>,
+) {
+    let Some(bounds) = board_bounds(&board, &boards) else {
+        return;
+    };
+    let (camera, mut transform, projection) = camera.into_inner();
+    let (Some(viewport), Projection::Orthographic(projection)) =
+        (camera.logical_viewport_size(), projection)
+    else {
+        return;
+    };
+
+    let half_view = viewport * projection.scale / 2.;
+    let margin = (bounds.half_size() - half_view).abs();
+    let position = transform.translation.truncate();
+    let clamped = position.clamp(bounds.center() - margin, bounds.center() + margin);
+    if clamped != position {
+        let z = transform.translation.z;
+        transform.translation = clamped.extend(z);
+    }
+}
+
+/// World rectangle covered by the board, which has no parent
+pub fn board_bounds(board: &Board, boards: &Query<&Transform, Without<Camera2d>>) -> Option<Rect> {
+    let anchor = boards.get(board.entity).ok()?.translation.truncate();
+    Some(Rect::from_corners(anchor, anchor + board_size(board)))
+}
+
+/// Projection scale showing the whole board
+fn fit_scale(board: &Board, camera: &Camera) -> f32 {
+    let Some(viewport) = camera.logical_viewport_size() else {
+        return 1.;
+    };
+    let scale = board_size(board) / viewport;
+    scale.x.max(scale.y)
+}
+
+pub fn board_size(board: &Board) -> Vec2 {
+    let map_size = (board.minefield.width(), board.minefield.height());
+    board_extent(board.minefield.topology(), map_size) * board.tile_size
+}
@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+
+#[cfg(feature = "simple_neighbors")]
+use crate::components::Neighbors;
+#[cfg(feature = "hierarchical_neighbors")]
+use crate::components::{GridChildOf, GridMap};
+#[cfg(all(feature = "hierarchical_neighbors", not(feature = "simple_neighbors")))]
+use crate::find_neighbors;
+use crate::{
+    components::Coordinates,
+    events::{PropagateUncoverEvent, TileChordEvent},
+    resources::Board,
+};
+
+pub fn chord_tiles(
+    event: On<TileChordEvent>,
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    children_query: Query<&Children>,
+    coordinates: Query<&Coordinates>,
+    #[cfg(feature = "simple_neighbors")] tiles: Query<&Neighbors>,
+    #[cfg(feature = "hierarchical_neighbors")] query_neighbors_2: Query<(&GridMap, &Coordinates)>,
+    #[cfg(feature = "hierarchical_neighbors")] query_neighbor_of: Query<&GridChildOf>,
+) {
+    let entity = event.0;
+    let Ok(&coords) = coordinates.get(entity) else {
+        return;
+    };
+    let Some(to_uncover) = board.play_chord(coords) else {
+        return;
+    };
+
+    #[cfg(not(any(feature = "simple_neighbors", feature = "hierarchical_neighbors")))]
+    let neighbors: Vec<Entity> = to_uncover
+        .iter()
+        .filter_map(|c| board.coords_map.get(c).copied())
+        .collect();
+    #[cfg(feature = "simple_neighbors")]
+    let neighbors: Vec<Entity> = match tiles.get(entity) {
+        Ok(neighbors) => neighbors.iter().flatten().copied().collect(),
+        Err(_) => return,
+    };
+    #[cfg(all(feature = "hierarchical_neighbors", not(feature = "simple_neighbors")))]
+    let neighbors = find_neighbors(
+        entity,
+        coords,
+        &board.minefield,
+        &query_neighbors_2,
+        &query_neighbor_of,
+    );
+
+    for neighbor in neighbors {
+        if coordinates
+            .get(neighbor)
+            .is_ok_and(|coords| to_uncover.contains(coords))
+        {
+            commands.trigger(PropagateUncoverEvent::new(neighbor, &children_query));
+        }
+    }
+}
@@ -0,0 +1,428 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    color::palettes::css::{GREEN, ORANGE, RED, YELLOW},
+    image::ImageSampler,
+    log,
+    mesh::{Indices, PrimitiveTopology},
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use minefield::{GameState, Minefield, Tile, TileState, Topology};
+use settings_plugin::resources::{BoardAssets, BoardOptions};
+
+use crate::{
+    HEX_HEIGHT,
+    components::{Chunk, Coordinates, MineCounter},
+    events::{BoardCompletedEvent, ChunkedTileEvent, GameEndEvent, TileAction, TileMark},
+    in_hexagon, place_bombs,
+    resources::{Board, CHUNKED_AREA, Chunks, Minimap},
+    systems::{
+        hud::update_mine_counter,
+        minimap::{BOMB_COLOR, COVERED_COLOR, UNCOVERED_COLOR, paint_tile},
+    },
+    tile_position, tile_sprite_size,
+};
+
+/// Width in pixels of a cell of the tile atlas
+const CELL_WIDTH: u32 = 32;
+/// Scale of the 3 by 5 pixels glyphs in the cells
+const GLYPH_SCALE: u32 = 4;
+
+/// Glyphs of the bomb counters from 1 to 8, one row of 3 bits per line from the top
+const DIGIT_GLYPHS: [[u8; 5]; 8] = [
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+];
+const FLAG_GLYPH: [u8; 5] = [0b110, 0b111, 0b110, 0b100, 0b100];
+const QUESTION_GLYPH: [u8; 5] = [0b111, 0b001, 0b011, 0b000, 0b010];
+const BOMB_GLYPH: [u8; 5] = [0b000, 0b010, 0b111, 0b010, 0b000];
+
+/// What a tile of a chunked board shows, drawn from a cell of the tile atlas
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum TileCell {
+    Covered,
+    Flag,
+    Question,
+    Empty,
+    Counter(u8),
+    /// The uncovered bomb which lost the game
+    Exploded,
+    /// Flagged bomb once the game is lost, or any bomb once it is won
+    BombFound,
+    /// Bomb left covered when the game is lost
+    BombMissed,
+    /// Flag on a safe tile when the game is lost
+    WrongFlag,
+}
+
+impl TileCell {
+    /// Number of cells of the tile atlas
+    const COUNT: u32 = 16;
+
+    fn of(minefield: &Minefield, chunks: &Chunks, coordinates: Coordinates) -> Self {
+        let (Some(tile), Some(state)) = (
+            minefield.tile_at(coordinates.into()),
+            minefield.state_at(coordinates.into()),
+        ) else {
+            return Self::Covered;
+        };
+        match (minefield.state(), state, tile) {
+            (_, TileState::Uncovered, Tile::Bomb) => Self::Exploded,
+            (GameState::Won, _, Tile::Bomb) => Self::BombFound,
+            (GameState::Lost, TileState::Flagged, Tile::Bomb) => Self::BombFound,
+            (GameState::Lost, TileState::Flagged, _) => Self::WrongFlag,
+            (GameState::Lost, TileState::Covered, Tile::Bomb) => Self::BombMissed,
+            // Every tile is shown once the game is lost, like the tile covers go away
+            (GameState::Lost, _, Tile::BombNeighbor(count))
+            | (_, TileState::Uncovered, Tile::BombNeighbor(count)) => Self::Counter(count),
+            (GameState::Lost, _, _) | (_, TileState::Uncovered, _) => Self::Empty,
+            (_, TileState::Flagged, _) => Self::Flag,
+            _ if chunks.questions.contains(&coordinates) => Self::Question,
+            _ => Self::Covered,
+        }
+    }
+
+    fn atlas_index(self) -> u32 {
+        match self {
+            Self::Covered => 0,
+            Self::Flag => 1,
+            Self::Question => 2,
+            Self::Empty => 3,
+            Self::Counter(count) => 3 + count.clamp(1, 8) as u32,
+            Self::Exploded => 12,
+            Self::BombFound => 13,
+            Self::BombMissed => 14,
+            Self::WrongFlag => 15,
+        }
+    }
+
+    /// Background and glyph of the cell
+    fn look(self, board_assets: &BoardAssets) -> (Color, Option<(&'static [u8; 5], Color)>) {
+        let covered = board_assets.covered_tile_material.color;
+        let uncovered = board_assets.tile_material.color;
+        let bomb = (&BOMB_GLYPH, Color::BLACK);
+        match self {
+            Self::Covered => (covered, None),
+            Self::Flag => (covered, Some((&FLAG_GLYPH, Color::from(RED)))),
+            Self::Question => (covered, Some((&QUESTION_GLYPH, Color::BLACK))),
+            Self::Empty => (uncovered, None),
+            Self::Counter(count) => (
+                uncovered,
+                Some((
+                    &DIGIT_GLYPHS[count.clamp(1, 8) as usize - 1],
+                    board_assets.bomb_counter_color(count),
+                )),
+            ),
+            Self::Exploded => (Color::from(RED), Some(bomb)),
+            Self::BombFound => (Color::from(GREEN), Some(bomb)),
+            Self::BombMissed => (Color::from(YELLOW), Some(bomb)),
+            Self::WrongFlag => (Color::from(ORANGE), Some((&FLAG_GLYPH, Color::BLACK))),
+        }
+    }
+
+    /// Color of the tile on the minimap
+    fn minimap_color(self) -> Color {
+        match self {
+            Self::Covered => COVERED_COLOR,
+            Self::Flag | Self::WrongFlag => Color::from(RED),
+            Self::Question => Color::from(YELLOW),
+            Self::Empty | Self::Counter(_) => UNCOVERED_COLOR,
+            Self::Exploded | Self::BombFound | Self::BombMissed => BOMB_COLOR,
+        }
+    }
+
+    /// Texture coordinates of the bottom left, bottom right, top right and top left corners
+    fn uvs(self) -> [[f32; 2]; 4] {
+        let u0 = self.atlas_index() as f32 / Self::COUNT as f32;
+        let u1 = (self.atlas_index() + 1) as f32 / Self::COUNT as f32;
+        [[u0, 1.], [u1, 1.], [u1, 0.], [u0, 0.]]
+    }
+}
+
+/// Cells the tile atlas is made of, in atlas order
+const CELLS: [TileCell; TileCell::COUNT as usize] = [
+    TileCell::Covered,
+    TileCell::Flag,
+    TileCell::Question,
+    TileCell::Empty,
+    TileCell::Counter(1),
+    TileCell::Counter(2),
+    TileCell::Counter(3),
+    TileCell::Counter(4),
+    TileCell::Counter(5),
+    TileCell::Counter(6),
+    TileCell::Counter(7),
+    TileCell::Counter(8),
+    TileCell::Exploded,
+    TileCell::BombFound,
+    TileCell::BombMissed,
+    TileCell::WrongFlag,
+];
+
+/// Draws boards larger than `CHUNKED_AREA` tiles with one mesh per chunk instead of entities
+/// for every tile
+pub fn spawn_chunks(
+    mut commands: Commands,
+    board: Res<Board>,
+    board_options: Option<Res<BoardOptions>>,
+    board_assets: Res<BoardAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    if board.minefield.area() <= CHUNKED_AREA {
+        return;
+    }
+    let padding = board_options.map_or(BoardOptions::default().tile_padding, |o| o.tile_padding);
+    let topology = board.minefield.topology();
+    let sprite_size = tile_sprite_size(topology, board.tile_size - padding);
+
+    let atlas = images.add(tile_atlas(topology, &board_assets));
+    let material = materials.add(ColorMaterial::from(atlas));
+    let mut chunks = Chunks::new((board.minefield.width(), board.minefield.height()));
+    log::info!("Drawing the board with {} chunks", chunks.len());
+    for index in 0..chunks.len() {
+        let mesh = meshes.add(chunk_mesh(&board, &chunks, index, sprite_size));
+        commands.spawn((
+            Name::new(format!("Chunk {}", index)),
+            Mesh2d(mesh.clone()),
+            MeshMaterial2d(material.clone()),
+            Transform::from_xyz(0., 0., 1.),
+            // Clicked tiles are found from the hit position
+            Pickable::default(),
+            Chunk { index },
+            ChildOf(board.entity),
+        ));
+        chunks.meshes.push(mesh);
+    }
+    commands.insert_resource(chunks);
+}
+
+/// Rebuilds the meshes of the chunks with changed tiles, and their part of the minimap
+pub fn rebuild_chunks(
+    mut chunks: ResMut<Chunks>,
+    board: Res<Board>,
+    minimap: Option<Res<Minimap>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    if !chunks.dirty.contains(&true) {
+        return;
+    }
+    let mut minimap_image = minimap.and_then(|minimap| images.get_mut(&minimap.image));
+    for index in 0..chunks.len() {
+        if !chunks.dirty[index] {
+            continue;
+        }
+        chunks.dirty[index] = false;
+
+        let cells: Vec<(Coordinates, TileCell)> = chunks
+            .tiles(index)
+            .map(|coords| (coords, TileCell::of(&board.minefield, &chunks, coords)))
+            .collect();
+        if let Some(mesh) = meshes.get_mut(&chunks.meshes[index]) {
+            let uvs: Vec<[f32; 2]> = cells.iter().flat_map(|(_, cell)| cell.uvs()).collect();
+            mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        }
+        if let Some(image) = minimap_image.as_deref_mut() {
+            for &(coords, cell) in &cells {
+                paint_tile(&board, image, coords, cell.minimap_color());
+            }
+        }
+    }
+}
+
+/// Plays a tile of a chunked board, like the tile events do for tile entities
+pub fn play_chunked_tile(
+    event: On<ChunkedTileEvent>,
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    mut chunks: ResMut<Chunks>,
+    board_options: Option<Res<BoardOptions>>,
+    board_assets: Res<BoardAssets>,
+    mut counters: Query<(&MineCounter, &mut Text)>,
+) {
+    let coords = event.coordinates;
+    match event.action {
+        TileAction::Uncover => {
+            if !board.play_uncover(coords) {
+                return;
+            }
+            // The first uncovered tile decides where the bombs can't be
+            if board.pending_bombs {
+                let options = match board_options {
+                    None => BoardOptions::default(), // If no options is set we use the default one
+                    Some(o) => o.clone(),
+                };
+                place_bombs(
+                    &mut commands,
+                    &mut board,
+                    &options,
+                    &board_assets,
+                    // No tile entities to fill on chunked boards
+                    std::iter::empty(),
+                    Some(coords),
+                );
+            }
+            uncover(&mut board, &mut chunks, coords);
+        }
+        TileAction::Mark(mark) => {
+            let current = chunks.mark_at(&board.minefield, coords);
+            if !board.play_mark(coords, current, mark) {
+                return;
+            }
+            if mark == TileMark::Question {
+                chunks.questions.insert(coords);
+            } else {
+                chunks.questions.remove(&coords);
+            }
+            update_mine_counter(&board, &mut counters);
+            chunks.mark_dirty(coords);
+        }
+        TileAction::Chord => {
+            let Some(to_uncover) = board.play_chord(coords) else {
+                return;
+            };
+            for neighbor in to_uncover {
+                uncover(&mut board, &mut chunks, neighbor);
+            }
+        }
+    }
+
+    match board.minefield.state() {
+        GameState::Playing => (),
+        GameState::Won => {
+            log::info!("Board completed");
+            chunks.mark_all_dirty();
+            commands.trigger(BoardCompletedEvent);
+        }
+        GameState::Lost => {
+            log::info!("Boom !");
+            chunks.mark_all_dirty();
+            commands.trigger(GameEndEvent {
+                message: "You lose!".into(),
+            });
+        }
+    }
+}
+
+/// Uncovers a tile and the empty area around it, marking the chunks to rebuild
+pub fn uncover(board: &mut Board, chunks: &mut Chunks, coords: Coordinates) {
+    for uncovered in board.minefield.uncover(coords.into()) {
+        let uncovered = Coordinates::from(uncovered);
+        chunks.questions.remove(&uncovered);
+        chunks.mark_dirty(uncovered);
+    }
+}
+
+/// Action of a mouse click on the tile at `coords` of a chunked board
+pub fn chunk_click_action(
+    board: &Board,
+    chunks: &Chunks,
+    coords: Coordinates,
+    button: PointerButton,
+    mouse_input: &ButtonInput<MouseButton>,
+    question_marks: bool,
+) -> Option<TileAction> {
+    // Chording is a middle click or a click while the other button is still held
+    let chord = match button {
+        PointerButton::Middle => true,
+        PointerButton::Primary => mouse_input.pressed(MouseButton::Right),
+        PointerButton::Secondary => mouse_input.pressed(MouseButton::Left),
+    };
+    match (board.minefield.state_at(coords.into())?, button) {
+        (TileState::Uncovered, _) => chord.then_some(TileAction::Chord),
+        (TileState::Covered, PointerButton::Primary) => Some(TileAction::Uncover),
+        (_, PointerButton::Secondary) => Some(TileAction::Mark(
+            chunks
+                .mark_at(&board.minefield, coords)
+                .next(question_marks),
+        )),
+        _ => None,
+    }
+}
+
+/// Mesh with a quad per tile of the chunk at `index`
+fn chunk_mesh(board: &Board, chunks: &Chunks, index: usize, sprite_size: Vec2) -> Mesh {
+    let topology = board.minefield.topology();
+    let half = sprite_size / 2.;
+    let mut positions = Vec::new();
+    let mut uvs = Vec::new();
+    let mut indices = Vec::new();
+    for coords in chunks.tiles(index) {
+        let center = tile_position(topology, coords, board.tile_size);
+        let first = positions.len() as u32;
+        for corner in [
+            Vec2::new(-half.x, -half.y),
+            Vec2::new(half.x, -half.y),
+            Vec2::new(half.x, half.y),
+            Vec2::new(-half.x, half.y),
+        ] {
+            positions.push((center + corner).extend(0.).to_array());
+        }
+        uvs.extend(TileCell::of(&board.minefield, chunks, coords).uvs());
+        indices.extend([first, first + 1, first + 2, first, first + 2, first + 3]);
+    }
+
+    // Kept in the main world to be rebuilt and picked
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Row of cells with the look of every tile state, hexagon shaped on hexagonal boards
+pub fn tile_atlas(topology: Topology, board_assets: &BoardAssets) -> Image {
+    let width = CELL_WIDTH;
+    let height = match topology {
+        Topology::Square => CELL_WIDTH,
+        Topology::Hexagonal => (CELL_WIDTH as f32 * HEX_HEIGHT).round() as u32,
+    };
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: width * TileCell::COUNT,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0; 4],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.sampler = ImageSampler::nearest();
+
+    // Glyphs are centered in their cell
+    let left = (width - 3 * GLYPH_SCALE) / 2;
+    let top = (height - 5 * GLYPH_SCALE) / 2;
+    for cell in CELLS {
+        let (background, glyph) = cell.look(board_assets);
+        let offset = cell.atlas_index() * width;
+        for y in 0..height {
+            for x in 0..width {
+                if topology == Topology::Hexagonal && !in_hexagon(x, y, width, height) {
+                    continue;
+                }
+                let glyph_color = glyph.and_then(|(rows, color)| {
+                    let (gx, gy) = (x.checked_sub(left)?, y.checked_sub(top)?);
+                    let (gx, gy) = (gx / GLYPH_SCALE, gy / GLYPH_SCALE);
+                    let row = rows.get(gy as usize)?;
+                    (gx < 3 && row >> (2 - gx) & 1 == 1).then_some(color)
+                });
+                let color = glyph_color.unwrap_or(background);
+                if let Err(e) = image.set_color_at(offset + x, y, color) {
+                    log::error!("Can't draw the tile atlas: {}", e);
+                }
+            }
+        }
+    }
+    image
+}
@@ -0,0 +1,153 @@
+use bevy::{log, prelude::*};
+use minefield::GameState;
+use settings_plugin::resources::BoardOptions;
+
+use crate::{
+    components::{Coordinates, Flag, Question, TileCover, TileCursor},
+    events::{
+        ChunkedTileEvent, TileAction, TileChordEvent, TileMark, TileMarkEvent, TileTriggerEvent,
+    },
+    resources::{Board, Chunks},
+    systems::mark::tile_mark,
+    tile_position,
+};
+
+/// Cursor moves of the arrow and WASD keys
+const CURSOR_KEYS: [(KeyCode, IVec2); 8] = [
+    (KeyCode::ArrowLeft, IVec2::NEG_X),
+    (KeyCode::ArrowRight, IVec2::X),
+    (KeyCode::ArrowDown, IVec2::NEG_Y),
+    (KeyCode::ArrowUp, IVec2::Y),
+    (KeyCode::KeyA, IVec2::NEG_X),
+    (KeyCode::KeyD, IVec2::X),
+    (KeyCode::KeyS, IVec2::NEG_Y),
+    (KeyCode::KeyW, IVec2::Y),
+];
+
+/// Moves the tile cursor and plays the selected tile: Space uncovers, F marks and E chords
+pub fn keyboard_input(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    board: Res<Board>,
+    board_options: Option<Res<BoardOptions>>,
+    cursor: Single<(&mut TileCursor, &mut Transform, &mut Visibility)>,
+    tiles: Query<(Entity, &Coordinates, &Children)>,
+    covers: Query<(), With<TileCover>>,
+    children: Query<&Children>,
+    flags: Query<(), With<Flag>>,
+    questions: Query<(), With<Question>>,
+    chunks: Option<Res<Chunks>>,
+) {
+    let moved = keys.any_just_pressed(CURSOR_KEYS.map(|(key, _)| key));
+    let uncover = keys.just_pressed(KeyCode::Space);
+    let mark = keys.just_pressed(KeyCode::KeyF);
+    let chord = keys.just_pressed(KeyCode::KeyE);
+    if !moved && !uncover && !mark && !chord {
+        return;
+    }
+
+    let (mut cursor, mut transform, mut visibility) = cursor.into_inner();
+    *visibility = Visibility::Inherited;
+    // The cursor stays on the board, even when its edges wrap around
+    let (width, height) = (board.minefield.width(), board.minefield.height());
+    let position = CURSOR_KEYS
+        .iter()
+        .filter(|(key, _)| keys.just_pressed(*key))
+        .fold(
+            IVec2::new(cursor.coordinates.x.into(), cursor.coordinates.y.into()),
+            |position, &(_, delta)| position + delta,
+        );
+    cursor.coordinates = Coordinates {
+        x: position.x.clamp(0, width as i32 - 1) as u16,
+        y: position.y.clamp(0, height as i32 - 1) as u16,
+    };
+    let z = transform.translation.z;
+    transform.translation = tile_position(
+        board.minefield.topology(),
+        cursor.coordinates,
+        board.tile_size,
+    )
+    .extend(z);
+
+    if board.minefield.state() != GameState::Playing {
+        return;
+    }
+    // Chunked boards have no tile entities, the chunk observer checks the tile state
+    if let Some(chunks) = chunks {
+        let question_marks = board_options.is_some_and(|o| o.question_marks);
+        let action = if uncover {
+            TileAction::Uncover
+        } else if mark {
+            TileAction::Mark(
+                chunks
+                    .mark_at(&board.minefield, cursor.coordinates)
+                    .next(question_marks),
+            )
+        } else if chord {
+            TileAction::Chord
+        } else {
+            return;
+        };
+        log::info!(
+            "Trying to play tile on {}: {:?}",
+            cursor.coordinates,
+            action
+        );
+        commands.trigger(ChunkedTileEvent {
+            coordinates: cursor.coordinates,
+            action,
+        });
+        return;
+    }
+    let Some((tile, _, tile_children)) = tiles
+        .iter()
+        .find(|&(_, &coords, _)| coords == cursor.coordinates)
+    else {
+        return;
+    };
+    let cover = tile_children.iter().find(|&child| covers.contains(child));
+
+    match cover {
+        Some(cover) => {
+            let current = tile_mark(cover, &children, &flags, &questions);
+            if uncover && current != TileMark::Flag {
+                log::info!("Trying to uncover tile on {}", cursor.coordinates);
+                commands.trigger(TileTriggerEvent(cover));
+            } else if mark {
+                log::info!("Trying to mark tile on {}", cursor.coordinates);
+                let question_marks = board_options.is_some_and(|o| o.question_marks);
+                commands.trigger(TileMarkEvent {
+                    entity: cover,
+                    mark: current.next(question_marks),
+                });
+            }
+        }
+        None if chord => {
+            log::info!("Trying to chord tile on {}", cursor.coordinates);
+            commands.trigger(TileChordEvent(tile));
+        }
+        None => (),
+    }
+}
+
+/// Scrolls the camera so that the tile cursor stays a tile away from the edges of the view
+pub fn follow_cursor(
+    board: Res<Board>,
+    cursor: Single<(&GlobalTransform, &Visibility), (With<TileCursor>, Changed<GlobalTransform>)>,
+    camera: Single<(&mut Transform, &Projection), With<Camera2d>>,
+) {
+    let (cursor_transform, visibility) = cursor.into_inner();
+    let (mut camera_transform, projection) = camera.into_inner();
+    let Projection::Orthographic(projection) = projection else {
+        return;
+    };
+    if *visibility == Visibility::Hidden {
+        return;
+    }
+
+    let view_center = camera_transform.translation.truncate() + projection.area.center();
+    let margin = (projection.area.half_size() - Vec2::splat(board.tile_size)).max(Vec2::ZERO);
+    let offset = cursor_transform.translation().truncate() - view_center;
+    let shift = offset - offset.clamp(-margin, margin);
+    camera_transform.translation += shift.extend(0.);
+}
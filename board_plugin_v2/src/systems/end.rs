@@ -1,15 +1,58 @@
 use bevy::{color::palettes::css::GRAY, log, prelude::*, ui_widgets::observe};
+use minefield::GameState;
+use settings_plugin::resources::{BoardOptions, Replay, Statistics};
 
 use crate::{
     components::{CountdownTimer, EndMessage},
     events::{CountdownEvent, GameEndEvent, RestartGameEvent},
-    resources::Board,
+    resources::{Board, ReplayPlayback},
 };
 
-pub fn on_game_end(event: On<GameEndEvent>, mut board: ResMut<Board>) {
+pub fn on_game_end(
+    event: On<GameEndEvent>,
+    mut board: ResMut<Board>,
+    board_options: Option<Res<BoardOptions>>,
+    replay_playback: Option<Res<ReplayPlayback>>,
+) {
     log::info!("{}", event.message);
     board.timer = Some(Timer::from_seconds(2.0, TimerMode::Once));
     board.end_message = event.message.clone();
+    board.stopwatch.pause();
+
+    let options = match board_options {
+        None => BoardOptions::default(), // If no options is set we use the default one
+        Some(o) => o.clone(),
+    };
+    let won = board.minefield.state() == GameState::Won;
+    let time = board.stopwatch.elapsed_secs();
+
+    if let Some(actions) = board.actions.take() {
+        Replay {
+            options: BoardOptions {
+                seed: Some(board.seed),
+                ..options.clone()
+            },
+            won,
+            actions,
+        }
+        .save();
+    }
+    // Watching a replay or the auto-player doesn't count as playing
+    if replay_playback.is_some() || board.autoplayed {
+        return;
+    }
+
+    let mut statistics = Statistics::load();
+    if let Some(rank) = statistics.record(&options, won, time) {
+        log::info!("New best time #{}: {:.1}s", rank + 1, time);
+        board.end_message = format!(
+            "{}\nNew best time #{}: {:.1}s",
+            board.end_message,
+            rank + 1,
+            time
+        );
+    }
+    statistics.save();
 }
 
 pub fn show_message(
@@ -58,7 +101,25 @@ pub fn show_message(
                 BackgroundColor(Color::from(GRAY)),
                 children![
                     (
-                        Text::new(format!("{}", board.end_message)),
+                        Text::new(board.end_message.clone()),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ),
+                    (
+                        Text::new(metrics_text(&board)),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ),
+                    (
+                        Text::new(format!("Seed: {}", board.seed)),
                         TextFont {
                             font: font.clone(),
                             font_size: 24.0,
@@ -94,6 +155,26 @@ pub fn show_message(
     commands.trigger(CountdownEvent::new(entity, remaining, &children));
 }
 
+/// Board difficulty, and for a won game how fast and efficiently it was cleared
+fn metrics_text(board: &Board) -> String {
+    let Some(metrics) = board.metrics else {
+        return String::new();
+    };
+    let mut text = format!(
+        "3BV: {} ({} opening(s), {} island(s))",
+        metrics.bbbv, metrics.openings, metrics.islands
+    );
+    if board.minefield.state() == GameState::Won {
+        if let Some(bbbv_per_second) = board.bbbv_per_second() {
+            text = format!("{}\n3BV/s: {:.2}", text, bbbv_per_second);
+        }
+        if let Some(efficiency) = board.efficiency() {
+            text = format!("{}\nEfficiency: {:.0}%", text, efficiency * 100.);
+        }
+    }
+    text
+}
+
 fn on_count_down(
     event: On<CountdownEvent>,
     mut commands: Commands,
@@ -0,0 +1,113 @@
+use bevy::{
+    color::palettes::css::{LIME, RED, YELLOW},
+    log,
+    prelude::*,
+};
+use minefield::GameState;
+use settings_plugin::resources::BoardAssets;
+
+use crate::{
+    components::{Coordinates, HintOverlay},
+    resources::{Board, HINTS_MAX_AREA, Hints},
+    tile_position,
+};
+
+/// Shows or hides the hint overlay with the H key
+pub fn toggle_hints(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    board: Res<Board>,
+    hints: Option<Res<Hints>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyH) {
+        return;
+    }
+    match hints {
+        Some(hints) => {
+            commands.entity(hints.overlay).despawn();
+            commands.remove_resource::<Hints>();
+        }
+        None if board.minefield.area() > HINTS_MAX_AREA => {
+            log::warn!(
+                "Hints are only shown on boards up to {} tiles",
+                HINTS_MAX_AREA
+            );
+        }
+        None => {
+            // Part of the board, so that it goes away with it
+            let overlay = commands
+                .spawn((
+                    Name::new("Hint overlay"),
+                    Transform::from_xyz(0., 0., 10.),
+                    Visibility::default(),
+                    HintOverlay,
+                    ChildOf(board.entity),
+                ))
+                .id();
+            commands.insert_resource(Hints {
+                overlay,
+                revision: None,
+            });
+        }
+    }
+}
+
+/// Redraws the hint overlay whenever the minefield changes: the bomb probability of every
+/// covered tile, safe ones in green, bombs in red and the best move in yellow
+pub fn update_hints(
+    mut commands: Commands,
+    board: Res<Board>,
+    board_assets: Res<BoardAssets>,
+    mut hints: ResMut<Hints>,
+) {
+    let revision = board.minefield.revision();
+    if hints.revision == Some(revision) {
+        return;
+    }
+    hints.revision = Some(revision);
+    commands.entity(hints.overlay).despawn_children();
+
+    // Before the first uncovered tile there is nothing to deduce from
+    if board.pending_bombs || board.minefield.state() != GameState::Playing {
+        return;
+    }
+    let probabilities = board.minefield.probabilities();
+    if !probabilities.exact {
+        log::warn!("Too many bomb layouts, hint probabilities are estimated");
+    }
+    let best = probabilities.best().map(|(coords, _)| coords);
+
+    let size = board.tile_size;
+    let topology = board.minefield.topology();
+    commands.entity(hints.overlay).with_children(|overlay| {
+        for (coords, probability) in probabilities.iter() {
+            let color = if Some(coords) == best {
+                Color::from(YELLOW)
+            } else {
+                Color::from(LIME).mix(&Color::from(RED), probability as f32)
+            };
+            let position = tile_position(topology, Coordinates::from(coords), size);
+            overlay
+                .spawn((
+                    Sprite {
+                        color: color.with_alpha(0.6),
+                        custom_size: Some(Vec2::splat(size * 0.5)),
+                        ..default()
+                    },
+                    Transform::from_translation(position.extend(0.)),
+                    // The overlay doesn't get in the way of the tiles
+                    Pickable::IGNORE,
+                ))
+                .with_child((
+                    Text2d::new(format!("{:.0}", probability * 100.)),
+                    TextFont {
+                        font: board_assets.bomb_counter_font.clone(),
+                        font_size: size * 0.3,
+                        ..default()
+                    },
+                    TextColor(Color::BLACK),
+                    Transform::from_xyz(0., 0., 1.),
+                ));
+        }
+    });
+}
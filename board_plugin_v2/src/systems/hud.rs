@@ -0,0 +1,119 @@
+use bevy::{color::palettes::css::GRAY, prelude::*, ui_widgets::observe};
+use minefield::GameState;
+
+use crate::{
+    components::{ElapsedTime, FaceButton, Hud, MineCounter},
+    events::RestartGameEvent,
+    resources::Board,
+};
+
+/// Spawns the HUD on top of the window: bombs left, restart button and elapsed time
+pub fn spawn_hud(commands: &mut Commands, font: Handle<Font>, bomb_count: u32) -> Entity {
+    let text_font = TextFont {
+        font,
+        font_size: 24.0,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Name::new("HUD"),
+            Node {
+                width: percent(100),
+                position_type: PositionType::Absolute,
+                top: px(0),
+                padding: px(8).all(),
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            Hud,
+            children![
+                (
+                    Name::new("Mine counter"),
+                    Text::new(format_counter(bomb_count as i32)),
+                    text_font.clone(),
+                    TextColor(Color::WHITE),
+                    MineCounter { bomb_count },
+                ),
+                (
+                    Name::new("Restart button"),
+                    Node {
+                        padding: px(4).all(),
+                        ..default()
+                    },
+                    BackgroundColor(Color::from(GRAY)),
+                    Button,
+                    FaceButton,
+                    children![(Text::new(face(GameState::Playing)), text_font.clone())],
+                    observe(on_restart_click),
+                ),
+                (
+                    Name::new("Elapsed time"),
+                    Text::new(format_counter(0)),
+                    text_font,
+                    TextColor(Color::WHITE),
+                    ElapsedTime,
+                ),
+            ],
+        ))
+        .id()
+}
+
+/// Shows the bombs left to flag, going negative when too many tiles are flagged
+pub fn update_mine_counter(board: &Board, counters: &mut Query<(&MineCounter, &mut Text)>) {
+    for (counter, mut text) in counters {
+        let remaining = counter.bomb_count as i32 - board.minefield.flag_count() as i32;
+        text.0 = format_counter(remaining);
+    }
+}
+
+/// Runs the game time while the game is unpaused
+pub fn tick_game_time(time: Res<Time>, mut board: ResMut<Board>) {
+    board.stopwatch.tick(time.delta());
+}
+
+/// Refreshes the game time and face of the HUD
+pub fn update_hud(
+    board: Res<Board>,
+    mut times: Query<&mut Text, With<ElapsedTime>>,
+    faces: Query<&Children, With<FaceButton>>,
+    mut face_texts: Query<&mut Text, Without<ElapsedTime>>,
+) {
+    let elapsed = format_counter(board.stopwatch.elapsed_secs() as i32);
+    for mut text in &mut times {
+        if text.0 != elapsed {
+            text.0 = elapsed.clone();
+        }
+    }
+
+    let face = face(board.minefield.state());
+    for children in faces {
+        for &child in children {
+            if let Ok(mut text) = face_texts.get_mut(child)
+                && text.0 != face
+            {
+                text.0 = face.to_string();
+            }
+        }
+    }
+}
+
+fn on_restart_click(
+    _click: On<Pointer<Click>>,
+    mut restart_game_writer: MessageWriter<RestartGameEvent>,
+) {
+    restart_game_writer.write(RestartGameEvent);
+}
+
+fn format_counter(value: i32) -> String {
+    format!("{:03}", value.clamp(-99, 999))
+}
+
+fn face(state: GameState) -> &'static str {
+    match state {
+        GameState::Playing => ":)",
+        GameState::Won => "B)",
+        GameState::Lost => ":(",
+    }
+}
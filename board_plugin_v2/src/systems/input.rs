@@ -1,8 +1,12 @@
 use bevy::{log, prelude::*};
+use settings_plugin::resources::BoardOptions;
 
 use crate::{
-    components::{Coordinates, Flag, TileCover},
-    events::{TileMarkEvent, TileTriggerEvent},
+    components::{BombNeighbor, Chunk, Coordinates, Flag, Question, TileCover},
+    events::{ChunkedTileEvent, TileChordEvent, TileMark, TileMarkEvent, TileTriggerEvent},
+    resources::{Board, CameraDrag, Chunks},
+    systems::{chunk::chunk_click_action, mark::tile_mark},
+    tile_at_position,
 };
 
 pub fn input_handling(
@@ -10,28 +14,88 @@ pub fn input_handling(
     tile_query: Query<&Coordinates>,
     cover_query: Query<&ChildOf, With<TileCover>>,
     flag_query: Query<(), With<Flag>>,
+    question_query: Query<(), With<Question>>,
+    children_query: Query<&Children>,
+    bomb_neighbor_query: Query<(), With<BombNeighbor>>,
+    chunk_query: Query<(), With<Chunk>>,
+    board_transforms: Query<&GlobalTransform>,
+    board: Option<Res<Board>>,
+    chunks: Option<Res<Chunks>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    board_options: Option<Res<BoardOptions>>,
+    camera_drag: Res<CameraDrag>,
     mut commands: Commands,
 ) {
+    // The left button was released after panning the camera
+    if click.button == PointerButton::Primary && camera_drag.is_panning() {
+        return;
+    }
     if let Ok(parent) = cover_query.get(click.entity) {
         if let Ok(&coordinates) = tile_query.get(parent.parent()) {
-            let original = click.original_event_target();
-            let is_flag = flag_query.get(original).is_ok();
+            // The mark sprite doesn't cover the whole tile, so the cover may be clicked directly
+            let mark = tile_mark(click.entity, &children_query, &flag_query, &question_query);
             match click.button {
                 PointerButton::Primary => {
                     log::info!("Trying to uncover tile on {}", coordinates);
-                    if !is_flag {
+                    if mark != TileMark::Flag {
                         commands.trigger(TileTriggerEvent(click.entity));
                     }
                 }
                 PointerButton::Secondary => {
                     log::info!("Trying to mark tile on {}", coordinates);
+                    let question_marks = board_options.is_some_and(|o| o.question_marks);
                     commands.trigger(TileMarkEvent {
                         entity: click.entity,
-                        mark: !is_flag,
+                        mark: mark.next(question_marks),
                     });
                 }
                 _ => (),
             }
         }
+    } else if bomb_neighbor_query.contains(click.entity) {
+        // Chording is a middle click or a click while the other button is still held
+        let chord = match click.button {
+            PointerButton::Middle => true,
+            PointerButton::Primary => mouse_input.pressed(MouseButton::Right),
+            PointerButton::Secondary => mouse_input.pressed(MouseButton::Left),
+        };
+        if chord {
+            if let Ok(coordinates) = tile_query.get(click.entity) {
+                log::info!("Trying to chord tile on {}", coordinates);
+            }
+            commands.trigger(TileChordEvent(click.entity));
+        }
+    } else if chunk_query.contains(click.entity) {
+        // Chunks of large boards are a single mesh, the tile is found from the hit position
+        let (Some(board), Some(chunks), Some(position)) = (board, chunks, click.hit.position)
+        else {
+            return;
+        };
+        let Ok(board_transform) = board_transforms.get(board.entity) else {
+            return;
+        };
+        let Some(coordinates) = tile_at_position(
+            board.minefield.topology(),
+            (board.minefield.width(), board.minefield.height()),
+            (position - board_transform.translation()).truncate(),
+            board.tile_size,
+        ) else {
+            return;
+        };
+        let question_marks = board_options.is_some_and(|o| o.question_marks);
+        if let Some(action) = chunk_click_action(
+            &board,
+            &chunks,
+            coordinates,
+            click.button,
+            &mouse_input,
+            question_marks,
+        ) {
+            log::info!("Trying to play tile on {}: {:?}", coordinates, action);
+            commands.trigger(ChunkedTileEvent {
+                coordinates,
+                action,
+            });
+        }
     }
 }
@@ -1,26 +1,107 @@
-use crate::{Board, BoardAssets, TileMarkEvent, components::Flag};
+use crate::{
+    Board, BoardAssets,
+    components::{Coordinates, Flag, MineCounter, Question},
+    events::{TileMark, TileMarkEvent},
+    resources::Minimap,
+    systems::{
+        hud::update_mine_counter,
+        minimap::{mark_color, paint_tile},
+    },
+};
 use bevy::prelude::*;
+use settings_plugin::resources::SpriteMaterial;
 
 pub fn mark_tiles(
     event: On<TileMarkEvent>,
     mut commands: Commands,
-    board: Res<Board>,
+    mut board: ResMut<Board>,
     board_assets: Res<BoardAssets>,
+    parents: Query<&ChildOf>,
+    coordinates: Query<&Coordinates>,
+    children: Query<&Children>,
+    flags: Query<(), With<Flag>>,
+    questions: Query<(), With<Question>>,
+    mut counters: Query<(&MineCounter, &mut Text)>,
+    minimap: Option<Res<Minimap>>,
+    mut images: ResMut<Assets<Image>>,
 ) {
-    if event.mark {
-        commands.entity(event.entity).with_child((
-            Name::new("Flag"),
-            Sprite {
-                custom_size: Some(Vec2::splat(board.tile_size)),
-                color: board_assets.flag_material.color,
-                image: board_assets.flag_material.texture.clone(),
-                ..default()
-            },
-            Transform::from_xyz(0., 0., 1.),
-            Pickable::default(),
-            Flag,
-        ));
-    } else {
-        commands.entity(event.entity).despawn_children();
+    let Ok(&coords) = parents
+        .get(event.entity)
+        .and_then(|parent| coordinates.get(parent.parent()))
+    else {
+        return;
+    };
+    // The minefield keeps the flags, question marks are only shown
+    let current = tile_mark(event.entity, &children, &flags, &questions);
+    if !board.play_mark(coords, current, event.mark) {
+        return;
     }
+    update_mine_counter(&board, &mut counters);
+
+    spawn_mark(
+        &mut commands,
+        event.entity,
+        event.mark,
+        &board_assets,
+        board.tile_size,
+    );
+    if let Some(image) = minimap.and_then(|minimap| images.get_mut(&minimap.image)) {
+        paint_tile(&board, image, coords, mark_color(event.mark));
+    }
+}
+
+/// Replaces the mark sprite of a tile cover
+pub fn spawn_mark(
+    commands: &mut Commands,
+    cover: Entity,
+    mark: TileMark,
+    board_assets: &BoardAssets,
+    tile_size: f32,
+) {
+    commands.entity(cover).despawn_children();
+    let sprite = |material: &SpriteMaterial| Sprite {
+        custom_size: Some(Vec2::splat(tile_size)),
+        color: material.color,
+        image: material.texture.clone(),
+        ..default()
+    };
+    match mark {
+        TileMark::Flag => {
+            commands.entity(cover).with_child((
+                Name::new("Flag"),
+                sprite(&board_assets.flag_material),
+                Transform::from_xyz(0., 0., 1.),
+                Pickable::default(),
+                Flag,
+            ));
+        }
+        TileMark::Question => {
+            commands.entity(cover).with_child((
+                Name::new("Question mark"),
+                sprite(&board_assets.question_material),
+                Transform::from_xyz(0., 0., 1.),
+                Pickable::default(),
+                Question,
+            ));
+        }
+        TileMark::None => (),
+    }
+}
+
+/// Current mark of a tile cover, from its mark sprite
+pub fn tile_mark(
+    cover: Entity,
+    children: &Query<&Children>,
+    flags: &Query<(), With<Flag>>,
+    questions: &Query<(), With<Question>>,
+) -> TileMark {
+    for child in children.iter_descendants(cover) {
+        if flags.contains(child) {
+            return TileMark::Flag;
+        }
+        if questions.contains(child) {
+            return TileMark::Question;
+        }
+    }
+    TileMark::None
 }
@@ -0,0 +1,182 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    color::palettes::css::{RED, YELLOW},
+    image::ImageSampler,
+    log,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    ui_widgets::observe,
+    window::PrimaryWindow,
+};
+
+use crate::{
+    components::{Coordinates, MinimapViewport, Uncover},
+    events::TileMark,
+    resources::{Board, CameraDrag, Minimap},
+    systems::camera::{board_bounds, board_size},
+};
+
+/// Length in pixels of the longest side of the minimap
+const MINIMAP_SIZE: f32 = 200.;
+
+/// Tile colors, shared with the chunks of large boards
+pub const COVERED_COLOR: Color = Color::srgb(0.4, 0.4, 0.4);
+pub const UNCOVERED_COLOR: Color = Color::srgb(0.85, 0.85, 0.85);
+pub const BOMB_COLOR: Color = Color::BLACK;
+
+/// Shows a minimap in the bottom right corner when the board is larger than the window
+pub fn spawn_minimap(
+    mut commands: Commands,
+    board: Res<Board>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let size = board_size(&board);
+    if size.x <= window.width() && size.y <= window.height() {
+        return;
+    }
+
+    let (width, height) = (
+        board.minefield.width() as u32,
+        board.minefield.height() as u32,
+    );
+    let mut image = Image::new_fill(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &COVERED_COLOR.to_srgba().to_u8_array(),
+        TextureFormat::Rgba8UnormSrgb,
+        // Kept in the main world to be painted tile by tile
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::nearest();
+    let image = images.add(image);
+
+    let scale = MINIMAP_SIZE / width.max(height) as f32;
+    let node = commands
+        .spawn((
+            Name::new("Minimap"),
+            Node {
+                position_type: PositionType::Absolute,
+                right: px(8),
+                bottom: px(8),
+                width: px(width as f32 * scale),
+                height: px(height as f32 * scale),
+                ..default()
+            },
+            ImageNode::new(image.clone()),
+            children![(
+                Name::new("Minimap viewport"),
+                Node {
+                    position_type: PositionType::Absolute,
+                    border: px(1).all(),
+                    ..default()
+                },
+                BorderColor::all(Color::WHITE),
+                // Clicks go through to the minimap
+                Pickable::IGNORE,
+                MinimapViewport,
+            )],
+            observe(on_minimap_click),
+        ))
+        .id();
+    commands.insert_resource(Minimap { node, image });
+}
+
+/// Paints the tile of a removed cover, once the minefield uncovered it
+pub fn paint_uncovered_tile(
+    event: On<Remove, Uncover>,
+    board: Res<Board>,
+    minimap: Option<Res<Minimap>>,
+    mut images: ResMut<Assets<Image>>,
+    parents: Query<&ChildOf>,
+    coordinates: Query<&Coordinates>,
+) {
+    let Some(minimap) = minimap else {
+        return;
+    };
+    let Ok(&coords) = parents
+        .get(event.entity)
+        .and_then(|parent| coordinates.get(parent.parent()))
+    else {
+        return;
+    };
+    let color = match board.minefield.tile_at(coords.into()) {
+        Some(tile) if tile.is_bomb() => BOMB_COLOR,
+        _ => UNCOVERED_COLOR,
+    };
+    if let Some(image) = images.get_mut(&minimap.image) {
+        paint_tile(&board, image, coords, color);
+    }
+}
+
+/// Minimap color of a covered tile with `mark`
+pub fn mark_color(mark: TileMark) -> Color {
+    match mark {
+        TileMark::None => COVERED_COLOR,
+        TileMark::Flag => Color::from(RED),
+        TileMark::Question => Color::from(YELLOW),
+    }
+}
+
+/// Moves the minimap rectangle over the part of the board in view
+pub fn update_minimap_viewport(
+    board: Res<Board>,
+    boards: Query<&Transform, Without<Camera2d>>,
+    camera: Single<(&Camera, &Transform, &Projection), With<Camera2d>>,
+    mut viewport: Single<&mut Node, With<MinimapViewport>>,
+) {
+    let Some(bounds) = board_bounds(&board, &boards) else {
+        return;
+    };
+    let (camera, transform, projection) = camera.into_inner();
+    let (Some(size), Projection::Orthographic(projection)) =
+        (camera.logical_viewport_size(), projection)
+    else {
+        return;
+    };
+
+    let view = Rect::from_center_size(transform.translation.truncate(), size * projection.scale)
+        .intersect(bounds);
+    // UI nodes are placed from the top left, the board from the bottom left
+    let node = Node {
+        left: percent((view.min.x - bounds.min.x) / bounds.width() * 100.),
+        top: percent((bounds.max.y - view.max.y) / bounds.height() * 100.),
+        width: percent(view.width() / bounds.width() * 100.),
+        height: percent(view.height() / bounds.height() * 100.),
+        ..viewport.clone()
+    };
+    viewport.set_if_neq(node);
+}
+
+/// Centers the camera on the clicked point of the minimap
+fn on_minimap_click(
+    click: On<Pointer<Click>>,
+    board: Res<Board>,
+    camera_drag: Res<CameraDrag>,
+    boards: Query<&Transform, Without<Camera2d>>,
+    mut camera: Single<&mut Transform, With<Camera2d>>,
+) {
+    if click.button != PointerButton::Primary || camera_drag.is_panning() {
+        return;
+    }
+    let (Some(position), Some(bounds)) = (click.hit.position, board_bounds(&board, &boards)) else {
+        return;
+    };
+    // Hits go from (-0.5, -0.5) in the top left corner to (0.5, 0.5) in the bottom right one
+    let fraction = Vec2::new(position.x + 0.5, 0.5 - position.y);
+    let target = bounds.min + fraction * bounds.size();
+    camera.translation = target.extend(camera.translation.z);
+}
+
+/// Paints the pixel of the tile at `coords` in the minimap image
+pub fn paint_tile(board: &Board, image: &mut Image, coords: Coordinates, color: Color) {
+    // Image rows go down, board rows go up
+    let y = board.minefield.height() - 1 - coords.y;
+    if let Err(e) = image.set_color_at(coords.x.into(), y.into(), color) {
+        log::error!("Can't paint the minimap: {}", e);
+    }
+}
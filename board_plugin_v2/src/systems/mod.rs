@@ -1,6 +1,17 @@
+pub mod autoplay;
+pub mod camera;
+pub mod chord;
+pub mod chunk;
+pub mod cursor;
 pub mod end;
+pub mod hint;
+pub mod hud;
 pub mod input;
 pub mod lose;
 pub mod mark;
+pub mod minimap;
+pub mod replay;
+pub mod save;
+pub mod theme;
 pub mod uncover;
 pub mod win;
@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use bevy::{log, prelude::*};
+use minefield::GameState;
+use settings_plugin::resources::ReplayActionKind;
+
+use crate::{
+    components::{Coordinates, TileCover, Uncover},
+    events::{
+        ChunkedTileEvent, TileAction, TileChordEvent, TileMark, TileMarkEvent, TileTriggerEvent,
+    },
+    resources::{Board, Chunks, ReplayPlayback},
+};
+
+/// Playback speed limits, changed with the up and down arrows
+const MIN_SPEED: f32 = 0.25;
+const MAX_SPEED: f32 = 16.0;
+
+/// Applies the recorded actions as the replay time reaches them
+pub fn play_replay(
+    mut commands: Commands,
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut board: ResMut<Board>,
+    tiles: Query<(Entity, &Coordinates, &Children)>,
+    covers: Query<(), With<TileCover>>,
+    uncovering: Query<(), With<Uncover>>,
+    chunks: Option<Res<Chunks>>,
+) {
+    if keys.just_pressed(KeyCode::ArrowUp) {
+        playback.speed = (playback.speed * 2.0).min(MAX_SPEED);
+        log::info!("Replay speed x{}", playback.speed);
+    }
+    if keys.just_pressed(KeyCode::ArrowDown) {
+        playback.speed = (playback.speed / 2.0).max(MIN_SPEED);
+        log::info!("Replay speed x{}", playback.speed);
+    }
+
+    if board.minefield.state() != GameState::Playing {
+        return;
+    }
+    playback.clock += time.delta_secs() * playback.speed;
+    board
+        .stopwatch
+        .set_elapsed(Duration::from_secs_f32(playback.clock));
+
+    // Uncovering spreads over several frames, the next action waits for it to settle
+    if !uncovering.is_empty() {
+        return;
+    }
+    let Some(&action) = playback.replay.actions.get(playback.next) else {
+        return;
+    };
+    if action.time > playback.clock {
+        return;
+    }
+    playback.next += 1;
+
+    let (x, y) = action.coordinates;
+    // Chunked boards have no tile entities, their tiles are played by coordinates
+    if chunks.is_some() {
+        let action = match action.kind {
+            ReplayActionKind::Uncover => TileAction::Uncover,
+            ReplayActionKind::Mark(true) => TileAction::Mark(TileMark::Flag),
+            ReplayActionKind::QuestionMark(true) => TileAction::Mark(TileMark::Question),
+            ReplayActionKind::Mark(false) | ReplayActionKind::QuestionMark(false) => {
+                TileAction::Mark(TileMark::None)
+            }
+            ReplayActionKind::Chord => TileAction::Chord,
+        };
+        commands.trigger(ChunkedTileEvent {
+            coordinates: Coordinates { x, y },
+            action,
+        });
+        return;
+    }
+    let Some((entity, _, children)) = tiles
+        .iter()
+        .find(|(_, coords, _)| coords.x == x && coords.y == y)
+    else {
+        log::error!("Replay action out of the board: {:?}", action);
+        return;
+    };
+    let cover = children.iter().find(|&child| covers.contains(child));
+
+    match (action.kind, cover) {
+        (ReplayActionKind::Uncover, Some(cover)) => {
+            commands.trigger(TileTriggerEvent(cover));
+        }
+        (ReplayActionKind::Mark(flag), Some(cover)) => {
+            commands.trigger(TileMarkEvent {
+                entity: cover,
+                mark: if flag { TileMark::Flag } else { TileMark::None },
+            });
+        }
+        (ReplayActionKind::QuestionMark(question), Some(cover)) => {
+            commands.trigger(TileMarkEvent {
+                entity: cover,
+                mark: if question {
+                    TileMark::Question
+                } else {
+                    TileMark::None
+                },
+            });
+        }
+        (ReplayActionKind::Chord, _) => {
+            commands.trigger(TileChordEvent(entity));
+        }
+        _ => log::warn!("Replay action on an uncovered tile: {:?}", action),
+    }
+}
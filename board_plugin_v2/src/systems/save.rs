@@ -0,0 +1,68 @@
+use bevy::{log, prelude::*};
+use minefield::{GameState, TileState};
+use settings_plugin::resources::{BoardOptions, SavedGame};
+
+use crate::{
+    components::{Coordinates, Question},
+    events::SaveGameEvent,
+    resources::{Board, Chunks},
+};
+
+pub fn save_game(
+    mut save_game_reader: MessageReader<SaveGameEvent>,
+    board: Res<Board>,
+    board_options: Option<Res<BoardOptions>>,
+    questions: Query<&ChildOf, With<Question>>,
+    parents: Query<&ChildOf>,
+    coordinates: Query<&Coordinates>,
+    chunks: Option<Res<Chunks>>,
+) {
+    if save_game_reader.read().count() == 0 {
+        return;
+    }
+    // Only a started and unfinished game is worth resuming
+    if board.pending_bombs || board.minefield.state() != GameState::Playing {
+        log::info!("No game in progress to save");
+        return;
+    }
+
+    let options = match board_options {
+        None => BoardOptions::default(), // If no options is set we use the default one
+        Some(o) => o.clone(),
+    };
+    let tiles_in = |state: TileState| -> Vec<(u16, u16)> {
+        board
+            .minefield
+            .tiles()
+            .filter(|&(coords, _)| board.minefield.state_at(coords) == Some(state))
+            .map(|(coords, _)| (coords.x, coords.y))
+            .collect()
+    };
+
+    SavedGame {
+        options,
+        seed: board.seed,
+        bombs: board
+            .minefield
+            .tiles()
+            .filter(|(_, tile)| tile.is_bomb())
+            .map(|(coords, _)| (coords.x, coords.y))
+            .collect(),
+        uncovered: tiles_in(TileState::Uncovered),
+        flagged: tiles_in(TileState::Flagged),
+        // Question marks only live in the sprites: question mark -> cover -> tile
+        questioned: questions
+            .iter()
+            .filter_map(|cover| parents.get(cover.parent()).ok())
+            .filter_map(|tile| coordinates.get(tile.parent()).ok())
+            // Chunked boards keep them aside
+            .chain(chunks.iter().flat_map(|chunks| chunks.questions.iter()))
+            .map(|coords| (coords.x, coords.y))
+            .collect(),
+        elapsed: board.stopwatch.elapsed_secs(),
+        clicks: board.clicks,
+        actions: board.actions.clone(),
+    }
+    .save();
+    log::info!("Game saved");
+}
@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+use minefield::{GameState, Topology};
+use settings_plugin::resources::{BoardAssets, SpriteMaterial};
+
+use crate::{
+    components::{
+        BoardBackground, Bomb, BombNeighbor, Chunk, Coordinates, Flag, Question, TileCover,
+    },
+    resources::Board,
+    systems::chunk::tile_atlas,
+};
+
+/// Redraws the spawned board with the current board assets, after a theme change
+pub fn reskin_board(
+    board: Res<Board>,
+    board_assets: Res<BoardAssets>,
+    mut sprites: Query<&mut Sprite>,
+    tiles: Query<Entity, (With<Coordinates>, With<Sprite>)>,
+    covers: Query<Entity, With<TileCover>>,
+    flags: Query<Entity, With<Flag>>,
+    questions: Query<Entity, With<Question>>,
+    backgrounds: Query<Entity, With<BoardBackground>>,
+    bombs: Query<&Children, With<Bomb>>,
+    bomb_neighbors: Query<(&BombNeighbor, &Children)>,
+    mut counters: Query<(&mut TextFont, &mut TextColor), With<Text2d>>,
+    chunks: Query<&MeshMaterial2d<ColorMaterial>, With<Chunk>>,
+    materials: Res<Assets<ColorMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let topology = board.minefield.topology();
+    // Hexagonal tiles and covers keep their hexagon mask, only tinted by the material
+    let masked = topology == Topology::Hexagonal;
+    let mut paint = |entity: Entity, material: &SpriteMaterial, masked: bool| {
+        if let Ok(mut sprite) = sprites.get_mut(entity) {
+            sprite.color = material.color;
+            if !masked {
+                sprite.image = material.texture.clone();
+            }
+        }
+    };
+
+    for entity in &backgrounds {
+        paint(entity, &board_assets.board_material, false);
+    }
+    // The tiles of an ended game show where the bombs were
+    if board.minefield.state() == GameState::Playing {
+        for entity in &tiles {
+            paint(entity, &board_assets.tile_material, masked);
+        }
+    }
+    for entity in &covers {
+        paint(entity, &board_assets.covered_tile_material, masked);
+    }
+    for entity in &flags {
+        paint(entity, &board_assets.flag_material, false);
+    }
+    for entity in &questions {
+        paint(entity, &board_assets.question_material, false);
+    }
+    for children in &bombs {
+        for child in children.iter().filter(|&child| !covers.contains(child)) {
+            paint(child, &board_assets.bomb_material, false);
+        }
+    }
+
+    for (bomb_neighbor, children) in &bomb_neighbors {
+        for &child in children {
+            if let Ok((mut font, mut color)) = counters.get_mut(child) {
+                font.font = board_assets.bomb_counter_font.clone();
+                color.0 = board_assets.bomb_counter_color(bomb_neighbor.count);
+            }
+        }
+    }
+
+    // Chunks share one material, its atlas is drawn again with the new colors
+    let atlas = chunks
+        .iter()
+        .next()
+        .and_then(|material| materials.get(&material.0)?.texture.clone());
+    if let Some(image) = atlas.and_then(|atlas| images.get_mut(&atlas)) {
+        *image = tile_atlas(topology, &board_assets);
+    }
+}
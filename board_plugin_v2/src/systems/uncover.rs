@@ -1,4 +1,6 @@
 use bevy::{log, prelude::*};
+use minefield::{GameState, Tile};
+use settings_plugin::resources::{BoardAssets, BoardOptions};
 
 #[cfg(feature = "simple_neighbors")]
 use crate::components::Neighbors;
@@ -7,14 +9,46 @@ use crate::components::{GridChildOf, GridMap};
 #[cfg(all(feature = "hierarchical_neighbors", not(feature = "simple_neighbors")))]
 use crate::find_neighbors;
 use crate::{
-    BoardOptions,
-    components::{Bomb, BombNeighbor, Coordinates, TileCover, Uncover},
+    components::{Coordinates, TileCover, Uncover},
     events::{BoardCompletedEvent, BombExplosionEvent, PropagateUncoverEvent, TileTriggerEvent},
+    place_bombs,
+    resources::Board,
 };
-#[cfg(not(any(feature = "simple_neighbors", feature = "hierarchical_neighbors")))]
-use crate::{SQUARE_COORDINATES, resources::Board};
 
-pub fn trigger_event_handler(event: On<TileTriggerEvent>, mut commands: Commands) {
+pub fn trigger_event_handler(
+    event: On<TileTriggerEvent>,
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    board_options: Option<Res<BoardOptions>>,
+    board_assets: Res<BoardAssets>,
+    covers: Query<&ChildOf, With<TileCover>>,
+    // Tiles are the only entities with coordinates and children
+    tiles: Query<(Entity, &Coordinates), With<Children>>,
+) {
+    let Ok((_, &coords)) = covers
+        .get(event.0)
+        .and_then(|parent| tiles.get(parent.parent()))
+    else {
+        return;
+    };
+    if !board.play_uncover(coords) {
+        return;
+    }
+    // The first uncovered tile decides where the bombs can't be
+    if board.pending_bombs {
+        let options = match board_options {
+            None => BoardOptions::default(), // If no options is set we use the default one
+            Some(o) => o.clone(),
+        };
+        place_bombs(
+            &mut commands,
+            &mut board,
+            &options,
+            &board_assets,
+            tiles.iter().map(|(entity, &coords)| (entity, coords)),
+            Some(coords),
+        );
+    }
     commands.entity(event.0).insert(Uncover);
 }
 
@@ -22,32 +56,12 @@ pub fn uncover_tiles(
     mut commands: Commands,
     children: Query<(Entity, &ChildOf), With<Uncover>>,
     children_query: Query<&Children>,
-    #[cfg(not(any(feature = "simple_neighbors", feature = "hierarchical_neighbors")))] board: Res<
-        Board,
-    >,
-    #[cfg(not(feature = "simple_neighbors"))] parents: Query<(
-        Option<&Bomb>,
-        Option<&BombNeighbor>,
-        &Coordinates,
-    )>,
-    #[cfg(feature = "simple_neighbors")] parents: Query<(
-        &Neighbors,
-        Option<&Bomb>,
-        Option<&BombNeighbor>,
-    )>,
-    cover_query: Query<(), (With<TileCover>, Without<Uncover>)>,
-    board_options: Option<Res<BoardOptions>>,
+    mut board: ResMut<Board>,
+    #[cfg(not(feature = "simple_neighbors"))] parents: Query<&Coordinates>,
+    #[cfg(feature = "simple_neighbors")] parents: Query<(&Neighbors, &Coordinates)>,
     #[cfg(feature = "hierarchical_neighbors")] query_neighbors_2: Query<(&GridMap, &Coordinates)>,
     #[cfg(feature = "hierarchical_neighbors")] query_neighbor_of: Query<&GridChildOf>,
 ) {
-    let options = match board_options {
-        None => BoardOptions::default(), // If no options is set we use the default one
-        Some(o) => o.clone(),
-    };
-    let bomb_count = options.bomb_count as usize;
-    let cover_count = cover_query.count();
-    let mut is_finished = false;
-
     // We iterate through tile covers to uncover
     for (entity, parent) in children.iter() {
         // we destroy the tile cover entity
@@ -56,7 +70,7 @@ pub fn uncover_tiles(
         let parent_entity = parent.parent();
 
         #[cfg(feature = "simple_neighbors")]
-        let (neighbors, bomb, bomb_counter) = match parents.get(parent_entity) {
+        let (neighbors, &coords) = match parents.get(parent_entity) {
             Ok(v) => v,
             Err(e) => {
                 log::error!("{}", e);
@@ -65,7 +79,7 @@ pub fn uncover_tiles(
         };
 
         #[cfg(not(feature = "simple_neighbors"))]
-        let (bomb, bomb_counter, &coords) = match parents.get(parent_entity) {
+        let &coords = match parents.get(parent_entity) {
             Ok(v) => v,
             Err(e) => {
                 log::error!("{}", e);
@@ -73,24 +87,28 @@ pub fn uncover_tiles(
             }
         };
 
-        if bomb.is_some() {
+        // The minefield keeps track of the game, tiles it refuses are already handled
+        let Some(tile) = board.minefield.reveal(coords.into()) else {
+            continue;
+        };
+
+        if tile.is_bomb() {
             log::info!("Boom !");
             commands.trigger(BombExplosionEvent(parent_entity));
             return;
         }
 
-        if cover_count == bomb_count && !is_finished {
+        if board.minefield.state() == GameState::Won {
             log::info!("Board completed");
             commands.trigger(BoardCompletedEvent);
-            is_finished = true;
         }
         // If the tile is empty..
-        if bomb_counter.is_none() {
+        if tile == Tile::Empty {
             #[cfg(not(any(feature = "simple_neighbors", feature = "hierarchical_neighbors")))]
-            for neighbor_entity in SQUARE_COORDINATES
-                .map(|tuple| coords + tuple)
-                .into_iter()
-                .filter_map(|c| board.coords_map.get(&c).copied())
+            for neighbor_entity in board
+                .minefield
+                .neighbors(coords.into())
+                .filter_map(|c| board.coords_map.get(&Coordinates::from(c)).copied())
             {
                 commands.trigger(PropagateUncoverEvent::new(neighbor_entity, &children_query));
             }
@@ -105,6 +123,7 @@ pub fn uncover_tiles(
             for neighbor_entity in find_neighbors(
                 parent_entity,
                 coords,
+                &board.minefield,
                 &query_neighbors_2,
                 &query_neighbor_of,
             ) {
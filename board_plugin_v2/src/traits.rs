@@ -19,7 +19,7 @@ impl<T: HasDescendants> Traversal<T> for DescendantsTraversal {
         let original = event.original();
 
         if current == original {
-            descendants.get(0).copied()
+            descendants.first().copied()
         } else {
             let pos = descendants.iter().position(|&e| e == current)?;
             descendants.get(pos + 1).copied()
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use bevy::prelude::Component;
 
 #[derive(Component)]
@@ -5,6 +7,15 @@ pub struct MenuUIRoot;
 
 #[derive(Component)]
 pub enum MenuButtonAction {
+    /// Resume the saved game
+    Continue,
     StartGame,
+    Statistics,
+    /// List the saved replays
+    Replays,
+    /// Play back the replay saved at this path
+    PlayReplay(PathBuf),
+    /// Back to the main menu buttons
+    Back,
     Quit,
 }
@@ -4,3 +4,9 @@ use bevy::prelude::Message;
 pub struct CreateGameEvent;
 #[derive(Debug, Copy, Clone, Message)]
 pub struct LoadSettingsEvent;
+/// Resumes the saved game, inserted as a resource along with its options
+#[derive(Debug, Copy, Clone, Message)]
+pub struct ContinueGameEvent;
+/// Plays the replay inserted as a resource along with its options
+#[derive(Debug, Copy, Clone, Message)]
+pub struct WatchReplayEvent;
@@ -8,7 +8,11 @@ use bevy::{
 };
 
 use components::{MenuButtonAction, MenuUIRoot};
-use events::LoadSettingsEvent;
+use events::{ContinueGameEvent, LoadSettingsEvent, WatchReplayEvent};
+use settings_plugin::resources::{BoardGeneration, BoardTopology, Replay, SavedGame, Statistics};
+
+/// Number of replays listed, the most recent ones
+const MAX_LISTED_REPLAYS: usize = 8;
 
 pub struct MainMenuPlugin<T> {
     pub running_state: T,
@@ -25,6 +29,8 @@ impl<T: States> Plugin for MainMenuPlugin<T> {
             )
             .add_systems(OnExit(self.running_state.clone()), Self::cleanup_menu);
         app.add_message::<LoadSettingsEvent>();
+        app.add_message::<ContinueGameEvent>();
+        app.add_message::<WatchReplayEvent>();
     }
 }
 
@@ -40,24 +46,182 @@ impl<T> MainMenuPlugin<T> {
                 ..default()
             },
             MenuUIRoot,
-            children![(
-                Node {
-                    flex_direction: FlexDirection::Column,
-                    align_items: AlignItems::Center,
-                    row_gap: Val::Px(50.0),
-                    ..default()
-                },
-                children![
-                    Self::button("Start game", font.clone(), MenuButtonAction::StartGame),
-                    Self::button("Quit", font.clone(), MenuButtonAction::Quit),
-                ]
-            )],
+            children![Self::main_view(font)],
         ));
 
         log::info!("Main menu initialized");
     }
 
-    fn button(label: &str, font: Handle<Font>, action: MenuButtonAction) -> impl Bundle {
+    fn main_view(font: Handle<Font>) -> impl Bundle {
+        (
+            Node {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(50.0),
+                ..default()
+            },
+            Children::spawn((
+                SpawnIter(
+                    SavedGame::exists()
+                        .then(|| Self::button("Continue", font.clone(), MenuButtonAction::Continue))
+                        .into_iter(),
+                ),
+                Spawn(Self::button(
+                    "Start game",
+                    font.clone(),
+                    MenuButtonAction::StartGame,
+                )),
+                Spawn(Self::button(
+                    "Statistics",
+                    font.clone(),
+                    MenuButtonAction::Statistics,
+                )),
+                Spawn(Self::button(
+                    "Replays",
+                    font.clone(),
+                    MenuButtonAction::Replays,
+                )),
+                Spawn(Self::button("Quit", font, MenuButtonAction::Quit)),
+            )),
+        )
+    }
+
+    /// Games played, wins and best times of every board configuration
+    fn statistics_view(font: Handle<Font>, statistics: &Statistics) -> impl Bundle {
+        let lines: Vec<String> = if statistics.boards.is_empty() {
+            vec!["No game played yet".into()]
+        } else {
+            statistics
+                .boards
+                .iter()
+                .map(|(key, board)| {
+                    let best_times = board
+                        .best_times
+                        .iter()
+                        .map(|time| format!("{:.1}s", time))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!(
+                        "{}x{}{}{}, {} bombs{}{}{}: {} played, {} won ({:.0}%), streak {} (best {}), best times: {}",
+                        key.map_size.0,
+                        key.map_size.1,
+                        match key.topology {
+                            BoardTopology::Square => "",
+                            BoardTopology::Hexagonal => " hex",
+                        },
+                        if key.wrap_edges { " wrapped" } else { "" },
+                        key.bomb_count,
+                        if key.safe_start { ", safe start" } else { "" },
+                        if key.first_click_safe {
+                            ", first click safe"
+                        } else {
+                            ""
+                        },
+                        match key.generation {
+                            BoardGeneration::Random => "",
+                            BoardGeneration::NoGuess => ", no guess",
+                        },
+                        board.games_played,
+                        board.games_won,
+                        board.win_rate(),
+                        board.win_streak,
+                        board.best_win_streak,
+                        if best_times.is_empty() { "-".into() } else { best_times },
+                    )
+                })
+                .collect()
+        };
+        let text_font = TextFont {
+            font: font.clone(),
+            font_size: 20.0,
+            ..default()
+        };
+
+        (
+            Node {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(16.0),
+                ..default()
+            },
+            Children::spawn((
+                Spawn((
+                    Text::new("Statistics"),
+                    TextFont {
+                        font: font.clone(),
+                        font_size: 32.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                )),
+                SpawnIter(lines.into_iter().map(move |line| {
+                    (Text::new(line), text_font.clone(), TextColor(Color::WHITE))
+                })),
+                Spawn(Self::button("Back", font, MenuButtonAction::Back)),
+            )),
+        )
+    }
+
+    /// Most recent replays, played back on click
+    fn replays_view(font: Handle<Font>) -> impl Bundle {
+        let replays = Replay::list();
+        let empty = replays.is_empty().then(|| {
+            (
+                Text::new("No replay yet"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            )
+        });
+        let button_font = font.clone();
+
+        (
+            Node {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(16.0),
+                ..default()
+            },
+            Children::spawn((
+                Spawn((
+                    Text::new("Replays"),
+                    TextFont {
+                        font: font.clone(),
+                        font_size: 32.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                )),
+                SpawnIter(empty.into_iter()),
+                SpawnIter(
+                    replays
+                        .into_iter()
+                        .take(MAX_LISTED_REPLAYS)
+                        .map(move |path| {
+                            let label = path
+                                .file_stem()
+                                .map(|stem| stem.to_string_lossy().into_owned())
+                                .unwrap_or_default();
+                            Self::button(
+                                label,
+                                button_font.clone(),
+                                MenuButtonAction::PlayReplay(path),
+                            )
+                        }),
+                ),
+                Spawn(Self::button("Back", font, MenuButtonAction::Back)),
+            )),
+        )
+    }
+
+    fn button(
+        label: impl Into<String>,
+        font: Handle<Font>,
+        action: MenuButtonAction,
+    ) -> impl Bundle {
         (
             Node {
                 width: Val::Px(250.0),
@@ -82,19 +246,64 @@ impl<T> MainMenuPlugin<T> {
     }
 
     fn menu_action(
+        mut commands: Commands,
         mut interaction_query: Query<
             (&Interaction, &MenuButtonAction),
             (Changed<Interaction>, With<Button>),
         >,
+        root: Query<Entity, With<MenuUIRoot>>,
+        asset_server: Res<AssetServer>,
         mut load_settings: MessageWriter<LoadSettingsEvent>,
+        mut continue_game: MessageWriter<ContinueGameEvent>,
+        mut watch_replay: MessageWriter<WatchReplayEvent>,
         mut exit: MessageWriter<AppExit>,
     ) {
         for (interaction, action) in &mut interaction_query {
             if *interaction == Interaction::Pressed {
                 match action {
+                    MenuButtonAction::Continue => match SavedGame::load() {
+                        Some(saved_game) => {
+                            commands.insert_resource(saved_game.options.clone());
+                            commands.insert_resource(saved_game);
+                            continue_game.write(ContinueGameEvent);
+                        }
+                        None => log::error!("No saved game to continue"),
+                    },
                     MenuButtonAction::StartGame => {
                         load_settings.write(LoadSettingsEvent);
                     }
+                    MenuButtonAction::Statistics => {
+                        let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+                        let root = root.single().unwrap();
+                        commands
+                            .entity(root)
+                            .despawn_children()
+                            .with_child(Self::statistics_view(font, &Statistics::load()));
+                    }
+                    MenuButtonAction::Replays => {
+                        let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+                        let root = root.single().unwrap();
+                        commands
+                            .entity(root)
+                            .despawn_children()
+                            .with_child(Self::replays_view(font));
+                    }
+                    MenuButtonAction::PlayReplay(path) => match Replay::load(path) {
+                        Some(replay) => {
+                            commands.insert_resource(replay.options.clone());
+                            commands.insert_resource(replay);
+                            watch_replay.write(WatchReplayEvent);
+                        }
+                        None => log::error!("Can't play replay {}", path.display()),
+                    },
+                    MenuButtonAction::Back => {
+                        let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+                        let root = root.single().unwrap();
+                        commands
+                            .entity(root)
+                            .despawn_children()
+                            .with_child(Self::main_view(font));
+                    }
                     MenuButtonAction::Quit => {
                         exit.write(AppExit::Success);
                     }
@@ -0,0 +1,301 @@
+//! Generates boards like the game does and plays them with the solver, without a window.
+//!
+//! ```text
+//! minesweeper-sim [--count N] [--width W] [--height H] [--bombs B] [--seed S]
+//!                 [--generation random|no-guess] [--topology square|hexagonal] [--wrap-edges]
+//!                 [--safe-start] [--first-click-safe] [--format csv|json]
+//! ```
+//!
+//! The defaults match the default `BoardOptions`. Boards use the seeds `S`, `S + 1`, ... so a
+//! run can be reproduced, and a board replayed in game with the same seed and options.
+
+use std::{
+    collections::BTreeMap,
+    env,
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use minefield::{
+    Coordinates, GameState, Generation, Minefield, NO_GUESS_MAX_AREA, Topology, random_seed,
+};
+
+const USAGE: &str = "Usage: minesweeper-sim [--count N] [--width W] [--height H] [--bombs B] \
+[--seed S] [--generation random|no-guess] [--topology square|hexagonal] [--wrap-edges] \
+[--safe-start] [--first-click-safe] [--format csv|json]";
+
+/// Simulation parameters, named after the `BoardOptions` fields
+#[derive(Debug)]
+struct Options {
+    count: u32,
+    map_size: (u16, u16),
+    bomb_count: u32,
+    seed: u64,
+    generation: Generation,
+    topology: Topology,
+    wrap_edges: bool,
+    safe_start: bool,
+    first_click_safe: bool,
+    json: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            count: 100,
+            map_size: (15, 15),
+            bomb_count: 30,
+            seed: random_seed(),
+            generation: Generation::Random,
+            topology: Topology::Square,
+            wrap_edges: false,
+            safe_start: false,
+            first_click_safe: false,
+            json: false,
+        }
+    }
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("Missing value for {}", arg));
+            match arg.as_str() {
+                "--count" => options.count = parse_number(&value()?)?,
+                "--width" => options.map_size.0 = parse_number(&value()?)?,
+                "--height" => options.map_size.1 = parse_number(&value()?)?,
+                "--bombs" => options.bomb_count = parse_number(&value()?)?,
+                "--seed" => options.seed = parse_number(&value()?)?,
+                "--generation" => {
+                    options.generation = match value()?.as_str() {
+                        "random" => Generation::Random,
+                        "no-guess" => Generation::NoGuess,
+                        other => return Err(format!("Unknown generation: {}", other)),
+                    }
+                }
+                "--topology" => {
+                    options.topology = match value()?.as_str() {
+                        "square" => Topology::Square,
+                        "hexagonal" => Topology::Hexagonal,
+                        other => return Err(format!("Unknown topology: {}", other)),
+                    }
+                }
+                "--wrap-edges" => options.wrap_edges = true,
+                "--safe-start" => options.safe_start = true,
+                "--first-click-safe" => options.first_click_safe = true,
+                "--format" => {
+                    options.json = match value()?.as_str() {
+                        "csv" => false,
+                        "json" => true,
+                        other => return Err(format!("Unknown format: {}", other)),
+                    }
+                }
+                "--help" | "-h" => return Err(USAGE.into()),
+                other => return Err(format!("Unknown argument: {}\n{}", other, USAGE)),
+            }
+        }
+
+        let (width, height) = options.map_size;
+        let area = width as u32 * height as u32;
+        if width == 0 || height == 0 {
+            return Err("The board can't be empty".into());
+        }
+        if options.bomb_count >= area {
+            return Err(format!("Too many bombs for {} tiles", area));
+        }
+        if options.generation == Generation::NoGuess && area as usize > NO_GUESS_MAX_AREA {
+            return Err(format!(
+                "No guess boards have at most {} tiles",
+                NO_GUESS_MAX_AREA
+            ));
+        }
+        Ok(options)
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(raw: &str) -> Result<T, String> {
+    raw.parse().map_err(|_| format!("Invalid number: {}", raw))
+}
+
+/// Outcome of one simulated game
+#[derive(Debug)]
+struct GameResult {
+    seed: u64,
+    won: bool,
+    /// Moves made without certainty, the first click included when it isn't protected
+    guesses: u32,
+    bbbv: u32,
+    attempts: u32,
+    solvable: bool,
+    generation_time: Duration,
+    solve_time: Duration,
+}
+
+/// Generates and plays one board the way the v2 board plugin sets it up
+fn simulate(options: &Options, seed: u64) -> GameResult {
+    let (width, height) = options.map_size;
+    let mut minefield = Minefield::empty(width, height)
+        .with_topology(options.topology)
+        .with_wrap_edges(options.wrap_edges);
+    // The simulated player always opens in the middle of the board
+    let first_click = Coordinates::new(width / 2, height / 2);
+
+    let started = Instant::now();
+    let start = options.first_click_safe.then_some(first_click);
+    let report = minefield
+        .generate(options.bomb_count, seed, options.generation, start)
+        .expect("bomb count checked with the options");
+    let generation_time = started.elapsed();
+    let bbbv = minefield.bbbv();
+
+    let started = Instant::now();
+    let mut guesses = 0;
+    // A no guess board is only solvable from its start, so it is always opened
+    let opened = if options.first_click_safe {
+        Some(first_click)
+    } else if options.safe_start || options.generation == Generation::NoGuess {
+        minefield.safe_start()
+    } else {
+        guesses += 1;
+        Some(first_click)
+    };
+    if let Some(coords) = opened {
+        minefield.uncover(coords);
+    }
+
+    while minefield.state() == GameState::Playing {
+        let probabilities = minefield.probabilities();
+        let safe: Vec<Coordinates> = probabilities
+            .iter()
+            .filter(|&(_, p)| p <= 0.)
+            .map(|(coords, _)| coords)
+            .collect();
+        if !safe.is_empty() {
+            for coords in safe {
+                minefield.uncover(coords);
+            }
+            for (coords, _) in probabilities.iter().filter(|&(_, p)| p >= 1.) {
+                minefield.set_flag(coords, true);
+            }
+            continue;
+        }
+        let Some((coords, _)) = probabilities.best() else {
+            break;
+        };
+        guesses += 1;
+        minefield.uncover(coords);
+    }
+
+    GameResult {
+        seed,
+        won: minefield.state() == GameState::Won,
+        guesses,
+        bbbv,
+        attempts: report.attempts,
+        solvable: report.solvable,
+        generation_time,
+        solve_time: started.elapsed(),
+    }
+}
+
+fn print_csv(results: &[GameResult]) {
+    println!("seed,won,guesses,bbbv,attempts,solvable,generation_ms,solve_ms");
+    for result in results {
+        println!(
+            "{},{},{},{},{},{},{:.3},{:.3}",
+            result.seed,
+            result.won,
+            result.guesses,
+            result.bbbv,
+            result.attempts,
+            result.solvable,
+            millis(result.generation_time),
+            millis(result.solve_time)
+        );
+    }
+}
+
+fn print_json(options: &Options, results: &[GameResult]) {
+    let count = results.len().max(1) as f64;
+    let wins = results.iter().filter(|result| result.won).count();
+    let mean = |value: fn(&GameResult) -> f64| results.iter().map(value).sum::<f64>() / count;
+
+    let mut bbbv_histogram: BTreeMap<u32, u32> = BTreeMap::new();
+    for result in results {
+        *bbbv_histogram.entry(result.bbbv).or_default() += 1;
+    }
+    let histogram = bbbv_histogram
+        .iter()
+        .map(|(bbbv, count)| format!("\"{}\": {}", bbbv, count))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let games = results
+        .iter()
+        .map(|result| {
+            format!(
+                "    {{\"seed\": {}, \"won\": {}, \"guesses\": {}, \"bbbv\": {}, \"attempts\": {}, \
+                 \"solvable\": {}, \"generation_ms\": {:.3}, \"solve_ms\": {:.3}}}",
+                result.seed,
+                result.won,
+                result.guesses,
+                result.bbbv,
+                result.attempts,
+                result.solvable,
+                millis(result.generation_time),
+                millis(result.solve_time)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    println!("{{");
+    println!(
+        "  \"map_size\": [{}, {}],",
+        options.map_size.0, options.map_size.1
+    );
+    println!("  \"bomb_count\": {},", options.bomb_count);
+    println!("  \"boards\": {},", results.len());
+    println!("  \"win_rate\": {:.4},", wins as f64 / count);
+    println!(
+        "  \"mean_guesses\": {:.3},",
+        mean(|result| result.guesses as f64)
+    );
+    println!("  \"mean_bbbv\": {:.3},", mean(|result| result.bbbv as f64));
+    println!("  \"bbbv_histogram\": {{{}}},", histogram);
+    println!(
+        "  \"mean_generation_ms\": {:.3},",
+        mean(|result| millis(result.generation_time))
+    );
+    println!(
+        "  \"mean_solve_ms\": {:.3},",
+        mean(|result| millis(result.solve_time))
+    );
+    println!("  \"games\": [\n{}\n  ]", games);
+    println!("}}");
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.
+}
+
+fn main() -> ExitCode {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let results: Vec<GameResult> = (0..options.count as u64)
+        .map(|i| simulate(&options, options.seed.wrapping_add(i)))
+        .collect();
+
+    if options.json {
+        print_json(&options, &results);
+    } else {
+        print_csv(&results);
+    }
+    ExitCode::SUCCESS
+}
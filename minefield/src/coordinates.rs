@@ -0,0 +1,33 @@
+use std::fmt::{self, Display, Formatter};
+
+/// Position of a tile on the minefield, `(0, 0)` being the bottom left corner
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Coordinates {
+    pub x: u16,
+    pub y: u16,
+}
+
+impl Coordinates {
+    pub const fn new(x: u16, y: u16) -> Self {
+        Self { x, y }
+    }
+
+    /// Applies a delta, returning `None` instead of wrapping below zero
+    pub fn checked_add(self, (x, y): (i8, i8)) -> Option<Self> {
+        let x = self.x.checked_add_signed(x as i16)?;
+        let y = self.y.checked_add_signed(y as i16)?;
+        Some(Self { x, y })
+    }
+}
+
+impl From<(u16, u16)> for Coordinates {
+    fn from((x, y): (u16, u16)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl Display for Coordinates {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
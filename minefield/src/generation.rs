@@ -0,0 +1,218 @@
+use std::{error::Error, fmt};
+
+use rand::{RngExt, SeedableRng, rngs::StdRng};
+
+use crate::{Coordinates, Minefield};
+
+/// Maximum number of layouts tried by [`Generation::NoGuess`] before settling for the last one
+pub const NO_GUESS_MAX_ATTEMPTS: u32 = 1000;
+/// Largest board [`Generation::NoGuess`] is run on, solving every attempt gets too slow past it
+pub const NO_GUESS_MAX_AREA: usize = 2_500;
+
+/// How bomb layouts are generated
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum Generation {
+    /// Bombs are placed anywhere
+    #[default]
+    Random,
+    /// Layouts are regenerated until the board can be solved from its start without guessing
+    NoGuess,
+}
+
+/// Outcome of a layout generation
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct GenerationReport {
+    /// Number of layouts generated
+    pub attempts: u32,
+    /// The layout was proven solvable without guessing
+    pub solvable: bool,
+}
+
+/// Reason a bomb layout can't be generated
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum GenerationError {
+    /// The bombs would leave no safe tile to start from
+    TooManyBombs { bomb_count: u32, area: usize },
+    /// The board is larger than [`NO_GUESS_MAX_AREA`] for a no guess generation
+    NoGuessTooLarge { area: usize },
+}
+
+impl fmt::Display for GenerationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooManyBombs { bomb_count, area } => write!(
+                f,
+                "{} bombs leave no safe tile on a map of {} tiles",
+                bomb_count, area
+            ),
+            Self::NoGuessTooLarge { area } => write!(
+                f,
+                "No guess boards have at most {} tiles, not {}",
+                NO_GUESS_MAX_AREA, area
+            ),
+        }
+    }
+}
+
+impl Error for GenerationError {}
+
+impl Minefield {
+    /// Places `bomb_count` bombs from a seed, the same seed and generation always giving the
+    /// same layout.
+    ///
+    /// When a `start` is given, it and its neighbors are kept free of bombs. Fails without
+    /// touching the map if the bombs leave no safe tile, or if a no guess board is too large
+    pub fn generate(
+        &mut self,
+        bomb_count: u32,
+        seed: u64,
+        generation: Generation,
+        start: Option<Coordinates>,
+    ) -> Result<GenerationReport, GenerationError> {
+        if bomb_count as usize >= self.area() {
+            return Err(GenerationError::TooManyBombs {
+                bomb_count,
+                area: self.area(),
+            });
+        }
+        if generation == Generation::NoGuess && self.area() > NO_GUESS_MAX_AREA {
+            return Err(GenerationError::NoGuessTooLarge { area: self.area() });
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        match generation {
+            Generation::Random => {
+                match start {
+                    Some(start) => self.set_bombs_around(bomb_count, start, &mut rng),
+                    None => self.set_bombs(bomb_count, &mut rng),
+                }
+                Ok(GenerationReport {
+                    attempts: 1,
+                    solvable: false,
+                })
+            }
+            Generation::NoGuess => {
+                let start = start.unwrap_or_else(|| {
+                    Coordinates::new(
+                        rng.random_range(0..self.width()),
+                        rng.random_range(0..self.height()),
+                    )
+                });
+                for attempts in 1..=NO_GUESS_MAX_ATTEMPTS {
+                    self.set_bombs_around(bomb_count, start, &mut rng);
+                    if self.is_solvable_from(start) {
+                        return Ok(GenerationReport {
+                            attempts,
+                            solvable: true,
+                        });
+                    }
+                }
+                Ok(GenerationReport {
+                    attempts: NO_GUESS_MAX_ATTEMPTS,
+                    solvable: false,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_guess_boards_are_solvable_from_their_start() {
+        let start = Coordinates::new(4, 4);
+        for seed in 0..20 {
+            let mut minefield = Minefield::empty(9, 9);
+            let report = minefield
+                .generate(10, seed, Generation::NoGuess, Some(start))
+                .unwrap();
+
+            assert!(report.solvable, "seed {}", seed);
+            assert_eq!(minefield.bomb_count(), 10);
+            assert_eq!(minefield.safe_start(), Some(start));
+            assert!(minefield.is_solvable_from(start), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn no_guess_boards_pick_a_start_when_none_is_given() {
+        for seed in 0..20 {
+            let mut minefield = Minefield::empty(9, 9);
+            let report = minefield
+                .generate(10, seed, Generation::NoGuess, None)
+                .unwrap();
+            let start = minefield.safe_start().unwrap();
+
+            assert!(report.solvable, "seed {}", seed);
+            assert!(minefield.is_solvable_from(start), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn generation_keeps_the_start_and_its_neighbors_safe() {
+        let start = Coordinates::new(0, 0);
+        for seed in 0..20 {
+            let mut minefield = Minefield::empty(5, 5);
+            minefield
+                .generate(16, seed, Generation::Random, Some(start))
+                .unwrap();
+
+            assert_eq!(minefield.bomb_count(), 16);
+            assert!(!minefield.is_bomb_at(start));
+            assert!(minefield.neighbors(start).all(|c| !minefield.is_bomb_at(c)));
+        }
+    }
+
+    #[test]
+    fn generation_fails_when_the_bombs_dont_fit() {
+        for generation in [Generation::Random, Generation::NoGuess] {
+            let mut minefield = Minefield::empty(3, 3);
+            let revision = minefield.revision();
+
+            assert_eq!(
+                minefield.generate(9, 0, generation, None),
+                Err(GenerationError::TooManyBombs {
+                    bomb_count: 9,
+                    area: 9
+                })
+            );
+            assert_eq!(
+                minefield.generate(12, 0, generation, Some(Coordinates::new(1, 1))),
+                Err(GenerationError::TooManyBombs {
+                    bomb_count: 12,
+                    area: 9
+                })
+            );
+            // The map is left untouched
+            assert_eq!(minefield.bomb_count(), 0);
+            assert_eq!(minefield.revision(), revision);
+        }
+    }
+
+    #[test]
+    fn no_guess_generation_fails_on_large_boards() {
+        let mut minefield = Minefield::empty(51, 50);
+        assert_eq!(
+            minefield.generate(400, 0, Generation::NoGuess, None),
+            Err(GenerationError::NoGuessTooLarge { area: 2550 })
+        );
+        assert_eq!(minefield.bomb_count(), 0);
+
+        assert!(minefield.generate(400, 0, Generation::Random, None).is_ok());
+        assert_eq!(minefield.bomb_count(), 400);
+    }
+
+    #[test]
+    fn a_start_surrounded_by_bombs_is_still_safe() {
+        // 8 bombs don't fit around the start of a 3x3 map, only the start is kept free
+        let start = Coordinates::new(1, 1);
+        let mut minefield = Minefield::empty(3, 3);
+        minefield
+            .generate(8, 0, Generation::Random, Some(start))
+            .unwrap();
+
+        assert_eq!(minefield.bomb_count(), 8);
+        assert!(!minefield.is_bomb_at(start));
+    }
+}
@@ -0,0 +1,51 @@
+//! Headless minesweeper model shared by the board plugins.
+//!
+//! Nothing in here depends on Bevy, so a whole game can be generated and played
+//! from tools or tests without an `App`.
+
+mod coordinates;
+mod generation;
+mod metrics;
+mod minefield;
+mod probability;
+mod solver;
+mod tile;
+mod topology;
+
+pub use coordinates::Coordinates;
+pub use generation::{
+    Generation, GenerationError, GenerationReport, NO_GUESS_MAX_AREA, NO_GUESS_MAX_ATTEMPTS,
+};
+pub use metrics::BoardMetrics;
+pub use minefield::{GameState, Minefield};
+pub use probability::{PROBABILITY_MAX_STEPS, Probabilities};
+pub use solver::Deductions;
+pub use tile::{Tile, TileState};
+pub use topology::Topology;
+
+use rand::{RngExt, rng};
+
+/// Picks a new random board seed
+pub fn random_seed() -> u64 {
+    rng().random()
+}
+
+/// Delta coordinates for all 8 square neighbors
+pub const SQUARE_COORDINATES: [(i8, i8); 8] = [
+    // Bottom left
+    (-1, -1),
+    // Bottom
+    (0, -1),
+    // Bottom right
+    (1, -1),
+    // Left
+    (-1, 0),
+    // Right
+    (1, 0),
+    // Top Left
+    (-1, 1),
+    // Top
+    (0, 1),
+    // Top right
+    (1, 1),
+];
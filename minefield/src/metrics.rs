@@ -0,0 +1,154 @@
+use std::collections::VecDeque;
+
+use crate::{Coordinates, Minefield, Tile};
+
+/// Difficulty of a bomb layout
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct BoardMetrics {
+    /// Bechtel's Board Benchmark Value: the minimum number of clicks uncovering every safe
+    /// tile, one per opening plus one per bomb counter no opening uncovers
+    pub bbbv: u32,
+    /// Areas of empty tiles, uncovered all at once along with their border
+    pub openings: u32,
+    /// Groups of touching bomb counters that no opening uncovers
+    pub islands: u32,
+}
+
+impl Minefield {
+    /// Computes the difficulty metrics of the bomb layout
+    pub fn metrics(&self) -> BoardMetrics {
+        let mut uncovered = vec![false; self.area()];
+        let mut metrics = BoardMetrics::default();
+
+        // Every opening is cleared with a single click, along with its border
+        for (i, (coords, tile)) in self.tiles().enumerate() {
+            if tile != Tile::Empty || uncovered[i] {
+                continue;
+            }
+            metrics.openings += 1;
+            self.flood(coords, &mut uncovered, |tile| tile == Tile::Empty);
+        }
+
+        // The remaining counters each need their own click
+        for (i, (coords, tile)) in self.tiles().enumerate() {
+            if !matches!(tile, Tile::BombNeighbor(_)) || uncovered[i] {
+                continue;
+            }
+            metrics.islands += 1;
+            metrics.bbbv += self.flood(coords, &mut uncovered, |tile| {
+                matches!(tile, Tile::BombNeighbor(_))
+            });
+        }
+
+        metrics.bbbv += metrics.openings;
+        metrics
+    }
+
+    /// Bechtel's Board Benchmark Value of the bomb layout, see [`BoardMetrics::bbbv`]
+    pub fn bbbv(&self) -> u32 {
+        self.metrics().bbbv
+    }
+
+    /// Marks the tiles reached from `start` through the tiles that `spreads`, and their safe
+    /// neighbors, as `uncovered`. Returns the number of tiles reached that `spreads`
+    fn flood(
+        &self,
+        start: Coordinates,
+        uncovered: &mut [bool],
+        spreads: impl Fn(Tile) -> bool,
+    ) -> u32 {
+        let mut count = 1;
+        uncovered[self.index(start)] = true;
+        let mut queue = VecDeque::from([start]);
+        while let Some(coords) = queue.pop_front() {
+            for neighbor in self.neighbors(coords) {
+                let index = self.index(neighbor);
+                let Some(tile) = self.tile_at(neighbor) else {
+                    continue;
+                };
+                if uncovered[index] || tile.is_bomb() {
+                    continue;
+                }
+                uncovered[index] = true;
+                if spreads(tile) {
+                    count += 1;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(width: u16, height: u16, bombs: &[(u16, u16)]) -> BoardMetrics {
+        let mut minefield = Minefield::empty(width, height);
+        minefield.set_bombs_at(bombs.iter().map(|&bomb| Coordinates::from(bomb)));
+        minefield.metrics()
+    }
+
+    #[test]
+    fn a_board_without_bombs_is_a_single_opening() {
+        assert_eq!(
+            metrics(3, 3, &[]),
+            BoardMetrics {
+                bbbv: 1,
+                openings: 1,
+                islands: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn openings_uncover_their_border() {
+        // _ 1 * 1 _
+        assert_eq!(
+            metrics(5, 1, &[(2, 0)]),
+            BoardMetrics {
+                bbbv: 2,
+                openings: 2,
+                islands: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn counters_away_from_openings_need_a_click_each() {
+        // 1 * 2 * 1
+        assert_eq!(
+            metrics(5, 1, &[(1, 0), (3, 0)]),
+            BoardMetrics {
+                bbbv: 3,
+                openings: 0,
+                islands: 3,
+            }
+        );
+        // Every tile around a single bomb is a counter, touching the next ones
+        assert_eq!(
+            metrics(3, 3, &[(1, 1)]),
+            BoardMetrics {
+                bbbv: 8,
+                openings: 0,
+                islands: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn openings_and_counters_add_up() {
+        // 1 1 1 _ _
+        // 2 * 2 _ _
+        // 2 * 3 1 1
+        // 1 1 2 * 1
+        // _ _ 1 1 1
+        // The 7 counters on the left edge and in the bottom right corner are out of reach of
+        // both openings
+        let metrics = metrics(5, 5, &[(1, 2), (1, 3), (3, 1)]);
+        assert_eq!(metrics.openings, 2);
+        assert_eq!(metrics.islands, 2);
+        assert_eq!(metrics.bbbv, 9);
+    }
+}
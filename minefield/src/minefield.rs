@@ -0,0 +1,533 @@
+use std::collections::VecDeque;
+
+use rand::{Rng, seq::SliceRandom};
+
+use crate::{Coordinates, Tile, TileState, Topology};
+
+/// Progress of a game
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum GameState {
+    /// The game is still running
+    #[default]
+    Playing,
+    /// Every safe tile was uncovered
+    Won,
+    /// A bomb was uncovered
+    Lost,
+}
+
+/// Complete minesweeper game: bomb layout and what the player uncovered or flagged
+#[derive(Debug, Clone)]
+pub struct Minefield {
+    width: u16,
+    height: u16,
+    topology: Topology,
+    wrap_edges: bool,
+    bomb_count: u32,
+    tiles: Vec<Tile>,
+    states: Vec<TileState>,
+    covered_count: usize,
+    flag_count: usize,
+    state: GameState,
+    start: Option<Coordinates>,
+    revision: u32,
+}
+
+impl Minefield {
+    /// Generates an empty, fully covered map
+    pub fn empty(width: u16, height: u16) -> Self {
+        let area = width as usize * height as usize;
+        Self {
+            width,
+            height,
+            topology: Topology::Square,
+            wrap_edges: false,
+            bomb_count: 0,
+            tiles: vec![Tile::Empty; area],
+            states: vec![TileState::Covered; area],
+            covered_count: area,
+            flag_count: 0,
+            state: GameState::Playing,
+            start: None,
+            revision: 0,
+        }
+    }
+
+    /// Changes the shape of the tiles, to be done before placing the bombs
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Makes the opposite edges touch each other, to be done before placing the bombs.
+    ///
+    /// Hexagonal boards with an odd height only wrap horizontally, their rows can't line up
+    /// across the top and bottom edges
+    pub fn with_wrap_edges(mut self, wrap_edges: bool) -> Self {
+        self.wrap_edges = wrap_edges;
+        self
+    }
+
+    #[cfg(feature = "debug")]
+    pub fn console_output(&self) -> String {
+        let mut buffer = format!(
+            "Map ({}, {}) with {} bombs:\n",
+            self.width, self.height, self.bomb_count
+        );
+        let line: String = (0..=(self.width + 1)).map(|_| '-').collect();
+        buffer = format!("{}{}\n", buffer, line);
+        for y in (0..self.height).rev() {
+            buffer = format!("{}|", buffer);
+            // Odd hexagon rows are shifted half a tile to the right
+            if self.topology == Topology::Hexagonal && y % 2 == 1 {
+                buffer = format!("{} ", buffer);
+            }
+            for x in 0..self.width {
+                let tile = self.tiles[self.index(Coordinates { x, y })];
+                buffer = format!("{}{}", buffer, tile.console_output());
+            }
+            buffer = format!("{}|\n", buffer);
+        }
+        format!("{}{}", buffer, line)
+    }
+
+    // Getter for `width`
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    // Getter for `height`
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    // Getter for `topology`
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    // Getter for `wrap_edges`
+    pub fn wrap_edges(&self) -> bool {
+        self.wrap_edges
+    }
+
+    // Getter for `bomb_count`
+    pub fn bomb_count(&self) -> u32 {
+        self.bomb_count
+    }
+
+    // Getter for `flag_count`
+    pub fn flag_count(&self) -> usize {
+        self.flag_count
+    }
+
+    // Getter for `state`
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    /// Number of changes to the bombs or to what the player sees, to tell when something
+    /// derived from the minefield is outdated
+    pub fn revision(&self) -> u32 {
+        self.revision
+    }
+
+    /// Number of tiles of the map
+    pub fn area(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn in_bounds(&self, coordinates: Coordinates) -> bool {
+        coordinates.x < self.width && coordinates.y < self.height
+    }
+
+    pub(crate) fn index(&self, coordinates: Coordinates) -> usize {
+        coordinates.y as usize * self.width as usize + coordinates.x as usize
+    }
+
+    fn coordinates(&self, index: usize) -> Coordinates {
+        Coordinates {
+            x: (index % self.width as usize) as u16,
+            y: (index / self.width as usize) as u16,
+        }
+    }
+
+    /// Content of the tile at `coordinates`, `None` if out of bounds
+    pub fn tile_at(&self, coordinates: Coordinates) -> Option<Tile> {
+        self.in_bounds(coordinates)
+            .then(|| self.tiles[self.index(coordinates)])
+    }
+
+    /// Visible state of the tile at `coordinates`, `None` if out of bounds
+    pub fn state_at(&self, coordinates: Coordinates) -> Option<TileState> {
+        self.in_bounds(coordinates)
+            .then(|| self.states[self.index(coordinates)])
+    }
+
+    /// Iterates over every tile of the map, row by row from the bottom
+    pub fn tiles(&self) -> impl Iterator<Item = (Coordinates, Tile)> + '_ {
+        self.tiles
+            .iter()
+            .enumerate()
+            .map(|(i, tile)| (self.coordinates(i), *tile))
+    }
+
+    /// In bounds neighbors of `coordinates`, according to the topology and the edge wrapping
+    pub fn neighbors(&self, coordinates: Coordinates) -> impl Iterator<Item = Coordinates> + '_ {
+        let offsets = self.topology.neighbor_offsets(coordinates);
+        offsets.iter().enumerate().filter_map(move |(i, &delta)| {
+            let neighbor = self.neighbor_at(coordinates, delta)?;
+            // On narrow wrapped boards, several deltas can lead to the same tile or back to it
+            let duplicate = neighbor == coordinates
+                || offsets[..i]
+                    .iter()
+                    .any(|&other| self.neighbor_at(coordinates, other) == Some(neighbor));
+            (!duplicate).then_some(neighbor)
+        })
+    }
+
+    /// Do the two tiles touch each other, across the wrapped edges too
+    pub fn are_neighbors(&self, a: Coordinates, b: Coordinates) -> bool {
+        self.neighbors(a).any(|coordinates| coordinates == b)
+    }
+
+    /// Tile at `delta` from `coordinates`, `None` if it is past an edge that doesn't wrap
+    fn neighbor_at(&self, coordinates: Coordinates, delta: (i8, i8)) -> Option<Coordinates> {
+        if !self.wrap_edges {
+            return coordinates
+                .checked_add(delta)
+                .filter(|&c| self.in_bounds(c));
+        }
+        let wrap_vertically = self.topology != Topology::Hexagonal || self.height.is_multiple_of(2);
+        let x = (coordinates.x as i32 + delta.0 as i32).rem_euclid(self.width as i32) as u16;
+        let y = coordinates.y as i32 + delta.1 as i32;
+        let y = if wrap_vertically {
+            y.rem_euclid(self.height as i32) as u16
+        } else {
+            u16::try_from(y).ok().filter(|&y| y < self.height)?
+        };
+        Some(Coordinates { x, y })
+    }
+
+    pub fn is_bomb_at(&self, coordinates: Coordinates) -> bool {
+        self.tile_at(coordinates).is_some_and(|tile| tile.is_bomb())
+    }
+
+    pub fn bomb_count_at(&self, coordinates: Coordinates) -> u8 {
+        if self.is_bomb_at(coordinates) {
+            return 0;
+        }
+        self.neighbors(coordinates)
+            .filter(|&c| self.is_bomb_at(c))
+            .count() as u8
+    }
+
+    /// Places `bomb_count` bombs at random and computes the bomb neighbor tiles
+    pub fn set_bombs<R: Rng + ?Sized>(&mut self, bomb_count: u32, rng: &mut R) {
+        self.place_random_bombs(bomb_count, rng, &[]);
+        self.start = None;
+    }
+
+    /// Places `bomb_count` bombs at random, keeping `start` and its neighbors free so that
+    /// uncovering `start` opens an area.
+    ///
+    /// Only `start` itself is kept free when the bombs don't fit around it
+    pub fn set_bombs_around<R: Rng + ?Sized>(
+        &mut self,
+        bomb_count: u32,
+        start: Coordinates,
+        rng: &mut R,
+    ) {
+        let mut excluded: Vec<Coordinates> = self.neighbors(start).collect();
+        excluded.push(start);
+        if self.area() - excluded.len() < bomb_count as usize {
+            excluded = vec![start];
+        }
+        self.place_random_bombs(bomb_count, rng, &excluded);
+        self.start = Some(start);
+    }
+
+    fn place_random_bombs<R: Rng + ?Sized>(
+        &mut self,
+        bomb_count: u32,
+        rng: &mut R,
+        excluded: &[Coordinates],
+    ) {
+        let mut indexes: Vec<usize> = (0..self.area())
+            .filter(|&i| !excluded.contains(&self.coordinates(i)))
+            .collect();
+        indexes.shuffle(rng);
+        self.set_bombs_at(
+            indexes
+                .into_iter()
+                .take(bomb_count as usize)
+                .map(|i| self.coordinates(i))
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    /// Places bombs at the given positions and computes the bomb neighbor tiles
+    pub fn set_bombs_at(&mut self, bombs: impl IntoIterator<Item = Coordinates>) {
+        self.revision = self.revision.wrapping_add(1);
+        self.tiles.fill(Tile::Empty);
+        self.bomb_count = 0;
+        for coordinates in bombs {
+            let index = self.index(coordinates);
+            if !self.tiles[index].is_bomb() {
+                self.tiles[index] = Tile::Bomb;
+                self.bomb_count += 1;
+            }
+        }
+        // Place bomb neighbors
+        for index in 0..self.area() {
+            if self.tiles[index].is_bomb() {
+                continue;
+            }
+            let num = self.bomb_count_at(self.coordinates(index));
+            if num > 0 {
+                self.tiles[index] = Tile::BombNeighbor(num);
+            }
+        }
+    }
+
+    /// Start tile chosen by the generator, or else the first empty tile of the map
+    pub fn safe_start(&self) -> Option<Coordinates> {
+        self.start.or_else(|| {
+            self.tiles
+                .iter()
+                .position(|tile| *tile == Tile::Empty)
+                .map(|i| self.coordinates(i))
+        })
+    }
+
+    /// Uncovers a single tile without propagating, returning its content.
+    ///
+    /// Returns `None` if the game is over or if the tile is out of bounds, flagged or
+    /// already uncovered
+    pub fn reveal(&mut self, coordinates: Coordinates) -> Option<Tile> {
+        if self.state != GameState::Playing || self.state_at(coordinates)? != TileState::Covered {
+            return None;
+        }
+        let index = self.index(coordinates);
+        self.states[index] = TileState::Uncovered;
+        self.covered_count -= 1;
+        self.revision = self.revision.wrapping_add(1);
+
+        let tile = self.tiles[index];
+        if tile.is_bomb() {
+            self.state = GameState::Lost;
+        } else if self.is_completed() {
+            self.state = GameState::Won;
+        }
+        Some(tile)
+    }
+
+    /// Uncovers a tile and flood-fills through empty tiles.
+    ///
+    /// Returns every tile that got uncovered, in uncovering order
+    pub fn uncover(&mut self, coordinates: Coordinates) -> Vec<Coordinates> {
+        let mut uncovered = Vec::new();
+        let mut queue = VecDeque::from([coordinates]);
+
+        while let Some(coordinates) = queue.pop_front() {
+            let Some(tile) = self.reveal(coordinates) else {
+                continue;
+            };
+            uncovered.push(coordinates);
+
+            if tile == Tile::Empty {
+                queue.extend(
+                    self.neighbors(coordinates)
+                        .filter(|&c| self.state_at(c) == Some(TileState::Covered)),
+                );
+            }
+        }
+
+        uncovered
+    }
+
+    /// Sets or removes a flag on a covered tile, returning `true` if the state changed
+    pub fn set_flag(&mut self, coordinates: Coordinates, flag: bool) -> bool {
+        let Some(state) = self.state_at(coordinates) else {
+            return false;
+        };
+        let new_state = match (state, flag) {
+            (TileState::Covered, true) => TileState::Flagged,
+            (TileState::Flagged, false) => TileState::Covered,
+            _ => return false,
+        };
+        let index = self.index(coordinates);
+        self.states[index] = new_state;
+        self.revision = self.revision.wrapping_add(1);
+        if flag {
+            self.flag_count += 1;
+        } else {
+            self.flag_count -= 1;
+        }
+        true
+    }
+
+    /// Toggles the flag of a covered tile, returning whether the tile is now flagged
+    pub fn toggle_flag(&mut self, coordinates: Coordinates) -> Option<bool> {
+        let flag = match self.state_at(coordinates)? {
+            TileState::Covered => true,
+            TileState::Flagged => false,
+            TileState::Uncovered => return None,
+        };
+        self.set_flag(coordinates, flag);
+        Some(flag)
+    }
+
+    /// Is every safe tile uncovered
+    pub fn is_completed(&self) -> bool {
+        self.covered_count == self.bomb_count as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Generation;
+
+    fn minefield(width: u16, height: u16, bombs: &[(u16, u16)]) -> Minefield {
+        let mut minefield = Minefield::empty(width, height);
+        minefield.set_bombs_at(bombs.iter().map(|&bomb| Coordinates::from(bomb)));
+        minefield
+    }
+
+    #[test]
+    fn uncover_floods_empty_tiles_up_to_the_bomb_counters() {
+        // A wall of bombs in the middle column
+        let mut minefield = minefield(5, 3, &[(2, 0), (2, 1), (2, 2)]);
+
+        let mut uncovered = minefield.uncover(Coordinates::new(0, 0));
+        uncovered.sort();
+
+        let expected: Vec<Coordinates> = (0..2)
+            .flat_map(|x| (0..3).map(move |y| Coordinates::new(x, y)))
+            .collect();
+        assert_eq!(uncovered, expected);
+        for y in 0..3 {
+            assert_eq!(
+                minefield.state_at(Coordinates::new(3, y)),
+                Some(TileState::Covered)
+            );
+        }
+        assert_eq!(minefield.state(), GameState::Playing);
+    }
+
+    #[test]
+    fn uncover_stops_on_a_bomb_counter() {
+        let mut minefield = minefield(3, 1, &[(0, 0)]);
+
+        assert_eq!(
+            minefield.uncover(Coordinates::new(1, 0)),
+            vec![Coordinates::new(1, 0)]
+        );
+        assert_eq!(
+            minefield.state_at(Coordinates::new(2, 0)),
+            Some(TileState::Covered)
+        );
+    }
+
+    #[test]
+    fn uncovering_the_last_safe_tile_wins() {
+        let mut minefield = minefield(3, 1, &[(0, 0)]);
+
+        minefield.uncover(Coordinates::new(1, 0));
+        assert_eq!(minefield.state(), GameState::Playing);
+        assert!(!minefield.is_completed());
+
+        minefield.uncover(Coordinates::new(2, 0));
+        assert_eq!(minefield.state(), GameState::Won);
+        assert!(minefield.is_completed());
+    }
+
+    #[test]
+    fn uncovering_a_bomb_loses() {
+        let mut minefield = minefield(3, 1, &[(0, 0)]);
+
+        assert_eq!(minefield.reveal(Coordinates::new(0, 0)), Some(Tile::Bomb));
+        assert_eq!(minefield.state(), GameState::Lost);
+        // Nothing can be uncovered once the game is over
+        assert!(minefield.uncover(Coordinates::new(2, 0)).is_empty());
+    }
+
+    #[test]
+    fn flags_toggle_on_covered_tiles_only() {
+        let mut minefield = minefield(3, 1, &[(0, 0)]);
+        let bomb = Coordinates::new(0, 0);
+
+        assert_eq!(minefield.toggle_flag(bomb), Some(true));
+        assert_eq!(minefield.state_at(bomb), Some(TileState::Flagged));
+        assert_eq!(minefield.flag_count(), 1);
+        // Flagged tiles can't be uncovered
+        assert!(minefield.uncover(bomb).is_empty());
+        assert!(!minefield.set_flag(bomb, true));
+
+        assert_eq!(minefield.toggle_flag(bomb), Some(false));
+        assert_eq!(minefield.state_at(bomb), Some(TileState::Covered));
+        assert_eq!(minefield.flag_count(), 0);
+
+        let counter = Coordinates::new(1, 0);
+        minefield.uncover(counter);
+        assert_eq!(minefield.toggle_flag(counter), None);
+        assert_eq!(minefield.toggle_flag(Coordinates::new(3, 0)), None);
+        assert_eq!(minefield.flag_count(), 0);
+    }
+
+    #[test]
+    fn revision_changes_with_what_the_player_sees() {
+        let mut minefield = Minefield::empty(3, 1);
+        let mut revision = minefield.revision();
+        let mut assert_bumped = |minefield: &Minefield| {
+            assert_ne!(minefield.revision(), revision);
+            revision = minefield.revision();
+        };
+
+        minefield.set_bombs_at([Coordinates::new(0, 0)]);
+        assert_bumped(&minefield);
+        minefield.set_flag(Coordinates::new(0, 0), true);
+        assert_bumped(&minefield);
+        minefield.uncover(Coordinates::new(1, 0));
+        assert_bumped(&minefield);
+
+        // Moves that change nothing keep the revision
+        let revision = minefield.revision();
+        minefield.uncover(Coordinates::new(1, 0));
+        minefield.set_flag(Coordinates::new(0, 0), true);
+        minefield.toggle_flag(Coordinates::new(1, 0));
+        assert_eq!(minefield.revision(), revision);
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_layout() {
+        let layout = |seed: u64, generation: Generation| {
+            let mut minefield = Minefield::empty(16, 16);
+            minefield
+                .generate(40, seed, generation, Some(Coordinates::new(8, 8)))
+                .unwrap();
+            minefield.tiles().collect::<Vec<_>>()
+        };
+
+        for generation in [Generation::Random, Generation::NoGuess] {
+            assert_eq!(layout(42, generation), layout(42, generation));
+            assert_ne!(layout(42, generation), layout(43, generation));
+        }
+    }
+
+    #[test]
+    fn wrapped_edges_make_opposite_tiles_neighbors() {
+        let corner = Coordinates::new(0, 0);
+        let opposite = Coordinates::new(4, 4);
+
+        let minefield = Minefield::empty(5, 5);
+        assert!(minefield.are_neighbors(corner, Coordinates::new(1, 1)));
+        assert!(!minefield.are_neighbors(corner, opposite));
+
+        let minefield = Minefield::empty(5, 5).with_wrap_edges(true);
+        assert!(minefield.are_neighbors(corner, opposite));
+        assert!(minefield.are_neighbors(corner, Coordinates::new(4, 0)));
+        assert!(!minefield.are_neighbors(corner, Coordinates::new(2, 2)));
+    }
+}
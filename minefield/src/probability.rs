@@ -0,0 +1,512 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use crate::{Coordinates, Minefield, TileState, solver::Constraint};
+
+/// Maximum number of partial layouts explored before settling for estimated probabilities
+pub const PROBABILITY_MAX_STEPS: usize = 1_000_000;
+
+/// Bomb probability of every covered tile, given what the player can see
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Probabilities {
+    values: BTreeMap<Coordinates, f64>,
+    /// Every layout was counted, otherwise the probabilities are estimated
+    pub exact: bool,
+}
+
+impl Probabilities {
+    /// Bomb probability of the covered tile at `coordinates`
+    pub fn get(&self, coordinates: Coordinates) -> Option<f64> {
+        self.values.get(&coordinates).copied()
+    }
+
+    /// Covered tiles with their bomb probability, row by row from the bottom
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinates, f64)> + '_ {
+        self.values.iter().map(|(&coords, &p)| (coords, p))
+    }
+
+    /// Safest covered tile, the first one in row order on ties
+    pub fn best(&self) -> Option<(Coordinates, f64)> {
+        self.iter().min_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// Frontier tiles sharing counters, whose layouts are counted together
+struct Component {
+    cells: Vec<Coordinates>,
+    /// Counters as local cell indexes and bomb count
+    constraints: Vec<(Vec<usize>, usize)>,
+}
+
+/// Layouts of a component, by number of bombs
+struct Layouts {
+    /// Number of layouts with `k` bombs, scaled
+    counts: Vec<f64>,
+    /// Number of layouts with `k` bombs where each cell holds one, scaled like `counts`.
+    ///
+    /// Left empty for bomb counts without any layout
+    cell_counts: Vec<Vec<f64>>,
+}
+
+impl Minefield {
+    /// Computes the bomb probability of every covered tile from the uncovered bomb counters,
+    /// the flags and the total bomb count.
+    ///
+    /// Flags are trusted like in [`Minefield::deduce`]
+    pub fn probabilities(&self) -> Probabilities {
+        let covered: Vec<Coordinates> = self
+            .tiles()
+            .map(|(coords, _)| coords)
+            .filter(|&c| self.state_at(c) == Some(TileState::Covered))
+            .collect();
+        let remaining = (self.bomb_count() as usize).saturating_sub(self.flag_count());
+        let constraints = self.constraints();
+
+        let components = components(&constraints);
+        let frontier_len: usize = components.iter().map(|c| c.cells.len()).sum();
+        let others_len = covered.len() - frontier_len;
+
+        let mut steps = 0;
+        let layouts: Option<Vec<Layouts>> = components
+            .iter()
+            .map(|component| component.layouts(&mut steps))
+            .collect();
+        let exact = layouts
+            .and_then(|layouts| combine(&components, &layouts, others_len, remaining))
+            .map(|mut values| {
+                let others_probability = values.remove(&None).unwrap_or_default();
+                let mut probabilities: BTreeMap<Coordinates, f64> = values
+                    .into_iter()
+                    .filter_map(|(coords, p)| Some((coords?, p)))
+                    .collect();
+                for &coords in &covered {
+                    probabilities.entry(coords).or_insert(others_probability);
+                }
+                probabilities
+            });
+
+        match exact {
+            Some(values) => Probabilities {
+                values,
+                exact: true,
+            },
+            None => Probabilities {
+                values: estimate(&covered, &constraints, remaining),
+                exact: false,
+            },
+        }
+    }
+}
+
+/// Groups the counters sharing covered tiles
+fn components(constraints: &[Constraint]) -> Vec<Component> {
+    let mut by_cell: HashMap<Coordinates, Vec<usize>> = HashMap::new();
+    for (i, constraint) in constraints.iter().enumerate() {
+        for &cell in &constraint.cells {
+            by_cell.entry(cell).or_default().push(i);
+        }
+    }
+
+    let mut visited = vec![false; constraints.len()];
+    let mut components = Vec::new();
+    for first in 0..constraints.len() {
+        if visited[first] {
+            continue;
+        }
+        visited[first] = true;
+
+        // Cells are listed in discovery order so that counters get settled early
+        let mut cells: Vec<Coordinates> = Vec::new();
+        let mut indexes: HashMap<Coordinates, usize> = HashMap::new();
+        let mut members = Vec::new();
+        let mut queue = VecDeque::from([first]);
+        while let Some(i) = queue.pop_front() {
+            members.push(i);
+            for &cell in &constraints[i].cells {
+                if indexes.contains_key(&cell) {
+                    continue;
+                }
+                indexes.insert(cell, cells.len());
+                cells.push(cell);
+                for &j in &by_cell[&cell] {
+                    if !visited[j] {
+                        visited[j] = true;
+                        queue.push_back(j);
+                    }
+                }
+            }
+        }
+
+        let constraints = members
+            .into_iter()
+            .map(|i| {
+                let constraint = &constraints[i];
+                let local = constraint.cells.iter().map(|cell| indexes[cell]).collect();
+                (local, constraint.mines as usize)
+            })
+            .collect();
+        components.push(Component { cells, constraints });
+    }
+    components
+}
+
+impl Component {
+    /// Counts the layouts satisfying every counter, `None` past [`PROBABILITY_MAX_STEPS`]
+    fn layouts(&self, steps: &mut usize) -> Option<Layouts> {
+        let len = self.cells.len();
+        let mut by_cell = vec![Vec::new(); len];
+        let mut unassigned = Vec::with_capacity(self.constraints.len());
+        for (i, (cells, _)) in self.constraints.iter().enumerate() {
+            for &cell in cells {
+                by_cell[cell].push(i);
+            }
+            unassigned.push(cells.len());
+        }
+
+        let mut search = Search {
+            component: self,
+            by_cell,
+            assigned: vec![0; self.constraints.len()],
+            unassigned,
+            layout: vec![false; len],
+            layouts: Layouts {
+                counts: vec![0.; len + 1],
+                cell_counts: vec![Vec::new(); len + 1],
+            },
+            steps,
+        };
+        search.explore(0, 0).then_some(search.layouts)
+    }
+}
+
+/// Depth first search of the bomb layouts of a component
+struct Search<'a> {
+    component: &'a Component,
+    /// Counters of each cell
+    by_cell: Vec<Vec<usize>>,
+    /// Bombs placed around each counter
+    assigned: Vec<usize>,
+    /// Cells left to decide around each counter
+    unassigned: Vec<usize>,
+    layout: Vec<bool>,
+    layouts: Layouts,
+    steps: &'a mut usize,
+}
+
+impl Search<'_> {
+    /// Decides the cells from `cell` on, `bombs` being already placed. Returns `false` once the
+    /// step budget is spent
+    fn explore(&mut self, cell: usize, bombs: usize) -> bool {
+        *self.steps += 1;
+        if *self.steps > PROBABILITY_MAX_STEPS {
+            return false;
+        }
+        if cell == self.layout.len() {
+            self.layouts.counts[bombs] += 1.;
+            let cell_counts = &mut self.layouts.cell_counts[bombs];
+            cell_counts.resize(self.layout.len(), 0.);
+            for (i, &bomb) in self.layout.iter().enumerate() {
+                if bomb {
+                    cell_counts[i] += 1.;
+                }
+            }
+            return true;
+        }
+
+        for bomb in [false, true] {
+            let mut possible = true;
+            for &i in &self.by_cell[cell] {
+                self.unassigned[i] -= 1;
+                self.assigned[i] += bomb as usize;
+                let mines = self.component.constraints[i].1;
+                if self.assigned[i] > mines || self.assigned[i] + self.unassigned[i] < mines {
+                    possible = false;
+                }
+            }
+            self.layout[cell] = bomb;
+            let completed = !possible || self.explore(cell + 1, bombs + bomb as usize);
+            for &i in &self.by_cell[cell] {
+                self.unassigned[i] += 1;
+                self.assigned[i] -= bomb as usize;
+            }
+            if !completed {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Weighs the layouts of every component by the ways of placing the remaining bombs in the
+/// `others_len` tiles away from the counters.
+///
+/// Returns the probability of every frontier cell, and the one of the other tiles under
+/// `None`, or `None` if no layout fits the bomb count
+fn combine(
+    components: &[Component],
+    layouts: &[Layouts],
+    others_len: usize,
+    remaining: usize,
+) -> Option<HashMap<Option<Coordinates>, f64>> {
+    // Bomb counts of all the components together, and of all of them but one
+    let total = layouts
+        .iter()
+        .fold(Distribution::default(), |acc, layouts| {
+            acc.convolve(&layouts.counts)
+        });
+    let ln_factorials = ln_factorials(others_len);
+    let ln_ways = |frontier_bombs: usize| {
+        let others_bombs = remaining.checked_sub(frontier_bombs)?;
+        (others_bombs <= others_len).then(|| {
+            ln_factorials[others_len]
+                - ln_factorials[others_bombs]
+                - ln_factorials[others_len - others_bombs]
+        })
+    };
+    // Weights are relative to the largest one to stay in range
+    let max_ln_ways = (0..total.counts.len())
+        .filter_map(ln_ways)
+        .reduce(f64::max)?;
+    let weight =
+        |frontier_bombs: usize| ln_ways(frontier_bombs).map_or(0., |ln| (ln - max_ln_ways).exp());
+
+    let total_weight: f64 = total
+        .counts
+        .iter()
+        .enumerate()
+        .map(|(k, &count)| count * weight(k))
+        .sum();
+    if total_weight <= 0. {
+        return None;
+    }
+
+    let mut probabilities = HashMap::new();
+    if others_len > 0 {
+        let others_bombs: f64 = total
+            .counts
+            .iter()
+            .enumerate()
+            .map(|(k, &count)| count * weight(k) * remaining.saturating_sub(k) as f64)
+            .sum();
+        probabilities.insert(None, others_bombs / others_len as f64 / total_weight);
+    }
+
+    for (c, component) in components.iter().enumerate() {
+        let rest = layouts
+            .iter()
+            .enumerate()
+            .filter(|&(other, _)| other != c)
+            .fold(Distribution::default(), |acc, (_, layouts)| {
+                acc.convolve(&layouts.counts)
+            });
+        // Both distributions are scaled differently
+        let rescale = (rest.ln_scale - total.ln_scale).exp();
+
+        // Weight of the layouts of this component with `k` bombs
+        let ways: Vec<f64> = (0..layouts[c].counts.len())
+            .map(|k| {
+                rest.counts
+                    .iter()
+                    .enumerate()
+                    .map(|(j, &count)| count * weight(k + j))
+                    .sum::<f64>()
+                    * rescale
+            })
+            .collect();
+
+        for (i, &cell) in component.cells.iter().enumerate() {
+            let bombs: f64 = layouts[c]
+                .cell_counts
+                .iter()
+                .zip(&ways)
+                .filter_map(|(cell_counts, ways)| Some(cell_counts.get(i)? * ways))
+                .sum();
+            probabilities.insert(Some(cell), bombs / total_weight);
+        }
+    }
+    Some(probabilities)
+}
+
+/// Number of layouts by number of bombs of several components together
+struct Distribution {
+    /// Number of layouts with `k` bombs, divided by `exp(ln_scale)` to stay in range
+    counts: Vec<f64>,
+    ln_scale: f64,
+}
+
+impl Default for Distribution {
+    /// No component, a single layout without bombs
+    fn default() -> Self {
+        Self {
+            counts: vec![1.],
+            ln_scale: 0.,
+        }
+    }
+}
+
+impl Distribution {
+    /// Adds a component with `counts` layouts by number of bombs
+    fn convolve(self, counts: &[f64]) -> Self {
+        let mut result = vec![0.; self.counts.len() + counts.len() - 1];
+        for (i, &x) in self.counts.iter().enumerate() {
+            for (j, &y) in counts.iter().enumerate() {
+                result[i + j] += x * y;
+            }
+        }
+        let max = result.iter().copied().fold(0., f64::max);
+        if max <= 0. {
+            return Self {
+                counts: result,
+                ln_scale: self.ln_scale,
+            };
+        }
+        result.iter_mut().for_each(|count| *count /= max);
+        Self {
+            counts: result,
+            ln_scale: self.ln_scale + max.ln(),
+        }
+    }
+}
+
+/// `ln(n!)` for every `n` up to `len`
+fn ln_factorials(len: usize) -> Vec<f64> {
+    let mut result = Vec::with_capacity(len + 1);
+    let mut sum = 0.;
+    result.push(sum);
+    for n in 1..=len {
+        sum += (n as f64).ln();
+        result.push(sum);
+    }
+    result
+}
+
+/// Rough probabilities for boards with too many layouts: the most demanding counter of each
+/// frontier tile, and the average density elsewhere
+fn estimate(
+    covered: &[Coordinates],
+    constraints: &[Constraint],
+    remaining: usize,
+) -> BTreeMap<Coordinates, f64> {
+    let density = remaining as f64 / covered.len().max(1) as f64;
+    let mut values: BTreeMap<Coordinates, f64> =
+        covered.iter().map(|&coords| (coords, density)).collect();
+    let mut frontier: HashMap<Coordinates, f64> = HashMap::new();
+    for constraint in constraints {
+        let p = constraint.mines as f64 / constraint.cells.len() as f64;
+        for &cell in &constraint.cells {
+            let value = frontier.entry(cell).or_insert(0.);
+            *value = value.max(p);
+        }
+    }
+    for (cell, p) in frontier {
+        values.insert(cell, p.min(1.));
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameState;
+
+    fn minefield(width: u16, height: u16, bombs: &[(u16, u16)]) -> Minefield {
+        let mut minefield = Minefield::empty(width, height);
+        minefield.set_bombs_at(bombs.iter().map(|&bomb| Coordinates::from(bomb)));
+        minefield
+    }
+
+    fn assert_probability(probabilities: &Probabilities, tile: (u16, u16), expected: f64) {
+        let p = probabilities.get(Coordinates::from(tile)).unwrap();
+        assert!(
+            (p - expected).abs() < 1e-9,
+            "{:?}: {} != {}",
+            tile,
+            p,
+            expected
+        );
+    }
+
+    #[test]
+    fn tiles_away_from_the_counters_share_the_remaining_bombs() {
+        // ? ?
+        // ? ?
+        // 1 ?   one bomb around the counter, the other one in the top row
+        let mut minefield = minefield(2, 3, &[(1, 1), (1, 2)]);
+        minefield.reveal(Coordinates::new(0, 0));
+
+        let probabilities = minefield.probabilities();
+        assert!(probabilities.exact);
+        assert_eq!(probabilities.get(Coordinates::new(0, 0)), None);
+        for tile in [(1, 0), (0, 1), (1, 1)] {
+            assert_probability(&probabilities, tile, 1. / 3.);
+        }
+        for tile in [(0, 2), (1, 2)] {
+            assert_probability(&probabilities, tile, 0.5);
+        }
+    }
+
+    #[test]
+    fn layouts_are_weighted_by_the_ways_of_placing_the_other_bombs() {
+        // ? 1 ? 1 ? ? ?   with 2 bombs
+        // Either the middle tile holds a bomb and the other one is in one of the 2 right
+        // tiles, or both bombs are around it: 3 layouts
+        let mut minefield = minefield(7, 1, &[(0, 0), (4, 0)]);
+        minefield.reveal(Coordinates::new(1, 0));
+        minefield.reveal(Coordinates::new(3, 0));
+
+        let probabilities = minefield.probabilities();
+        assert!(probabilities.exact);
+        assert_probability(&probabilities, (2, 0), 2. / 3.);
+        for tile in [(0, 0), (4, 0), (5, 0), (6, 0)] {
+            assert_probability(&probabilities, tile, 1. / 3.);
+        }
+        assert_eq!(
+            probabilities.best().map(|(coords, _)| coords),
+            Some(Coordinates::new(0, 0))
+        );
+    }
+
+    #[test]
+    fn flags_are_trusted() {
+        // F 1 ? ?
+        let mut minefield = minefield(4, 1, &[(0, 0)]);
+        minefield.reveal(Coordinates::new(1, 0));
+        minefield.set_flag(Coordinates::new(0, 0), true);
+
+        let probabilities = minefield.probabilities();
+        assert!(probabilities.exact);
+        assert_eq!(probabilities.get(Coordinates::new(0, 0)), None);
+        assert_probability(&probabilities, (2, 0), 0.);
+        assert_probability(&probabilities, (3, 0), 0.);
+    }
+
+    #[test]
+    fn probabilities_are_estimated_past_the_step_budget() {
+        // Counters on every other tile of every other row make a single frontier with far
+        // too many layouts to count
+        let bombs: Vec<(u16, u16)> = (0..41)
+            .flat_map(|x| (0..41).map(move |y| (x, y)))
+            .filter(|&(x, y)| (x % 2 == 1 || y % 2 == 1) && (x * 7 + y * 13) % 3 == 0)
+            .collect();
+        let mut minefield = minefield(41, 41, &bombs);
+        for x in (0..41).step_by(2) {
+            for y in (0..41).step_by(2) {
+                minefield.reveal(Coordinates::new(x, y));
+            }
+        }
+        assert_eq!(minefield.state(), GameState::Playing);
+
+        let probabilities = minefield.probabilities();
+        assert!(!probabilities.exact);
+        // Every covered tile still gets a probability
+        let covered = minefield
+            .tiles()
+            .filter(|&(coords, _)| minefield.state_at(coords) == Some(TileState::Covered))
+            .count();
+        assert_eq!(probabilities.iter().count(), covered);
+        assert!(probabilities.iter().all(|(_, p)| (0. ..=1.).contains(&p)));
+    }
+}
@@ -0,0 +1,268 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{Coordinates, GameState, Minefield, Tile, TileState};
+
+/// Moves that are certain given what the player can see
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Deductions {
+    /// Covered tiles that can't hold a bomb
+    pub safe: Vec<Coordinates>,
+    /// Covered tiles that must hold a bomb
+    pub mines: Vec<Coordinates>,
+}
+
+impl Deductions {
+    pub fn is_empty(&self) -> bool {
+        self.safe.is_empty() && self.mines.is_empty()
+    }
+}
+
+/// A visible bomb counter: `mines` bombs are hidden somewhere among `cells`
+#[derive(Debug, Clone)]
+pub(crate) struct Constraint {
+    pub(crate) cells: Vec<Coordinates>,
+    pub(crate) mines: u8,
+}
+
+impl Minefield {
+    /// Constraints given by every uncovered bomb counter bordering covered tiles.
+    ///
+    /// Flags are trusted and removed from the cells and bomb counts
+    pub(crate) fn constraints(&self) -> Vec<Constraint> {
+        let mut constraints = Vec::new();
+        for (coords, tile) in self.tiles() {
+            let Tile::BombNeighbor(count) = tile else {
+                continue;
+            };
+            if self.state_at(coords) != Some(TileState::Uncovered) {
+                continue;
+            }
+            let mut cells = Vec::new();
+            let mut flags = 0;
+            for neighbor in self.neighbors(coords) {
+                match self.state_at(neighbor) {
+                    Some(TileState::Covered) => cells.push(neighbor),
+                    Some(TileState::Flagged) => flags += 1,
+                    _ => (),
+                }
+            }
+            if cells.is_empty() {
+                continue;
+            }
+            cells.sort();
+            constraints.push(Constraint {
+                cells,
+                mines: count.saturating_sub(flags),
+            });
+        }
+        constraints
+    }
+
+    /// Finds the covered tiles that are certainly safe or certainly bombs, using only the
+    /// uncovered bomb counters, the flags and the total bomb count
+    pub fn deduce(&self) -> Deductions {
+        let constraints = self.constraints();
+        let mut safe = BTreeSet::new();
+        let mut mines = BTreeSet::new();
+
+        // A single counter is either satisfied or needs all its covered neighbors
+        for constraint in &constraints {
+            if constraint.mines == 0 {
+                safe.extend(constraint.cells.iter().copied());
+            } else if constraint.mines as usize == constraint.cells.len() {
+                mines.extend(constraint.cells.iter().copied());
+            }
+        }
+
+        // Two overlapping counters constrain the tiles they don't share
+        let mut by_cell: HashMap<Coordinates, Vec<usize>> = HashMap::new();
+        for (i, constraint) in constraints.iter().enumerate() {
+            for &cell in &constraint.cells {
+                by_cell.entry(cell).or_default().push(i);
+            }
+        }
+        for (i, a) in constraints.iter().enumerate() {
+            let overlapping: BTreeSet<usize> = a
+                .cells
+                .iter()
+                .flat_map(|cell| by_cell[cell].iter().copied())
+                .filter(|&j| j != i)
+                .collect();
+
+            for j in overlapping {
+                let b = &constraints[j];
+                let shared = a.cells.iter().filter(|c| b.cells.contains(c)).count();
+                let a_only: Vec<Coordinates> = a
+                    .cells
+                    .iter()
+                    .filter(|c| !b.cells.contains(c))
+                    .copied()
+                    .collect();
+                let b_only_count = b.cells.len() - shared;
+
+                // The shared tiles hold at least what `b` can't fit in its own tiles..
+                let shared_min = (b.mines as usize).saturating_sub(b_only_count);
+                // .. so if that is all `a` needs, the rest of `a` is safe
+                if !a_only.is_empty() && a.mines as usize == shared_min {
+                    safe.extend(a_only.iter().copied());
+                }
+                // The shared tiles hold at most what `b` has, `a` needs the remaining ones
+                // in its own tiles
+                let shared_max = shared.min(b.mines as usize);
+                let a_only_min = (a.mines as usize).saturating_sub(shared_max);
+                if !a_only.is_empty() && a_only_min == a_only.len() {
+                    mines.extend(a_only.iter().copied());
+                }
+            }
+        }
+
+        // The total bomb count settles the end game
+        let unknown: Vec<Coordinates> = self
+            .tiles()
+            .map(|(coords, _)| coords)
+            .filter(|&c| self.state_at(c) == Some(TileState::Covered))
+            .collect();
+        let remaining = (self.bomb_count() as usize).saturating_sub(self.flag_count());
+        if remaining == 0 {
+            safe.extend(unknown);
+        } else if remaining == unknown.len() {
+            mines.extend(unknown);
+        }
+
+        Deductions {
+            safe: safe.into_iter().collect(),
+            mines: mines.into_iter().collect(),
+        }
+    }
+
+    /// Plays a copy of the board from `start` using pure deduction, telling if it can be
+    /// completed without ever guessing
+    pub fn is_solvable_from(&self, start: Coordinates) -> bool {
+        let mut field = self.clone();
+        field.uncover(start);
+
+        loop {
+            match field.state() {
+                GameState::Won => return true,
+                GameState::Lost => return false,
+                GameState::Playing => (),
+            }
+
+            let deductions = field.deduce();
+            if deductions.is_empty() {
+                return false;
+            }
+            for coords in deductions.mines {
+                field.set_flag(coords, true);
+            }
+            for coords in deductions.safe {
+                field.uncover(coords);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minefield(width: u16, height: u16, bombs: &[(u16, u16)]) -> Minefield {
+        let mut minefield = Minefield::empty(width, height);
+        minefield.set_bombs_at(bombs.iter().map(|&bomb| Coordinates::from(bomb)));
+        minefield
+    }
+
+    /// Uncovers the given tiles one by one, without flood-filling
+    fn reveal(minefield: &mut Minefield, tiles: &[(u16, u16)]) {
+        for &tile in tiles {
+            minefield.reveal(Coordinates::from(tile));
+        }
+    }
+
+    #[test]
+    fn satisfied_counters_free_their_neighbors() {
+        // ? ? ?
+        // 1 1 ?   with the bomb on the top left
+        let mut minefield = minefield(3, 2, &[(0, 1)]);
+        reveal(&mut minefield, &[(0, 0), (1, 0)]);
+
+        // The left counter's bomb is also the middle counter's, so its other tiles are safe
+        let deductions = minefield.deduce();
+        assert_eq!(
+            deductions.safe,
+            vec![Coordinates::new(2, 0), Coordinates::new(2, 1)]
+        );
+        assert!(deductions.mines.is_empty());
+    }
+
+    #[test]
+    fn overlapping_counters_find_the_bombs() {
+        // ? ? ?
+        // 1 2 1   with bombs on the top corners
+        let mut minefield = minefield(3, 2, &[(0, 1), (2, 1)]);
+        reveal(&mut minefield, &[(0, 0), (1, 0), (2, 0)]);
+
+        let deductions = minefield.deduce();
+        assert!(deductions.safe.is_empty());
+        assert_eq!(
+            deductions.mines,
+            vec![Coordinates::new(0, 1), Coordinates::new(2, 1)]
+        );
+
+        // Once flagged, the counters are satisfied
+        for coords in deductions.mines {
+            minefield.set_flag(coords, true);
+        }
+        let deductions = minefield.deduce();
+        assert_eq!(deductions.safe, vec![Coordinates::new(1, 1)]);
+        assert!(deductions.mines.is_empty());
+    }
+
+    #[test]
+    fn counters_needing_every_neighbor_find_the_bombs() {
+        let mut minefield = minefield(3, 1, &[(2, 0)]);
+        minefield.uncover(Coordinates::new(0, 0));
+
+        let deductions = minefield.deduce();
+        assert!(deductions.safe.is_empty());
+        assert_eq!(deductions.mines, vec![Coordinates::new(2, 0)]);
+    }
+
+    #[test]
+    fn the_bomb_count_settles_the_end_game() {
+        // The only counter sees none of the remaining tiles
+        let mut minefield = minefield(4, 1, &[(0, 0)]);
+        reveal(&mut minefield, &[(1, 0)]);
+        minefield.set_flag(Coordinates::new(0, 0), true);
+
+        let deductions = minefield.deduce();
+        assert_eq!(
+            deductions.safe,
+            vec![Coordinates::new(2, 0), Coordinates::new(3, 0)]
+        );
+    }
+
+    #[test]
+    fn fifty_fifty_needs_a_guess() {
+        // ? 1 .
+        // ? 1 .   the bomb is in either left tile
+        let mut minefield = minefield(3, 2, &[(0, 1)]);
+        minefield.uncover(Coordinates::new(2, 0));
+
+        assert_eq!(
+            minefield.state_at(Coordinates::new(0, 0)),
+            Some(TileState::Covered)
+        );
+        assert!(minefield.deduce().is_empty());
+        assert!(!minefield.is_solvable_from(Coordinates::new(2, 0)));
+    }
+
+    #[test]
+    fn openings_without_guesses_are_solvable() {
+        let minefield = minefield(5, 5, &[(4, 4)]);
+
+        assert!(minefield.is_solvable_from(Coordinates::new(0, 0)));
+        // Starting on a bomb loses
+        assert!(!minefield.is_solvable_from(Coordinates::new(4, 4)));
+    }
+}
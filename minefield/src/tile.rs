@@ -1,7 +1,7 @@
 #[cfg(feature = "debug")]
 use colored::Colorize;
 
-/// Enum describing a Minesweeper tile
+/// Enum describing the content of a Minesweeper tile
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Tile {
     /// Is a bomb
@@ -35,3 +35,22 @@ impl Tile {
         )
     }
 }
+
+/// What the player currently sees of a tile
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum TileState {
+    /// Not uncovered yet
+    #[default]
+    Covered,
+    /// Covered and marked with a flag
+    Flagged,
+    /// Uncovered
+    Uncovered,
+}
+
+impl TileState {
+    /// Is the tile still hidden to the player?
+    pub const fn is_covered(&self) -> bool {
+        !matches!(self, Self::Uncovered)
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{Coordinates, SQUARE_COORDINATES};
+
+/// Delta coordinates for the 6 neighbors of a hexagon on an even row
+const HEX_EVEN_ROW_COORDINATES: [(i8, i8); 6] = [
+    // Bottom left
+    (-1, -1),
+    // Bottom right
+    (0, -1),
+    // Left
+    (-1, 0),
+    // Right
+    (1, 0),
+    // Top left
+    (-1, 1),
+    // Top right
+    (0, 1),
+];
+
+/// Delta coordinates for the 6 neighbors of a hexagon on an odd row
+const HEX_ODD_ROW_COORDINATES: [(i8, i8); 6] = [
+    // Bottom left
+    (0, -1),
+    // Bottom right
+    (1, -1),
+    // Left
+    (-1, 0),
+    // Right
+    (1, 0),
+    // Top left
+    (0, 1),
+    // Top right
+    (1, 1),
+];
+
+/// Shape of the tiles and which tiles touch each other
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Topology {
+    /// Square tiles with 8 neighbors
+    #[default]
+    Square,
+    /// Pointy-top hexagons with 6 neighbors, odd rows being shifted half a tile to the right
+    Hexagonal,
+}
+
+impl Topology {
+    /// Delta coordinates of the neighbors of the tile at `coordinates`
+    pub fn neighbor_offsets(self, coordinates: Coordinates) -> &'static [(i8, i8)] {
+        match self {
+            Self::Square => &SQUARE_COORDINATES,
+            Self::Hexagonal if coordinates.y.is_multiple_of(2) => &HEX_EVEN_ROW_COORDINATES,
+            Self::Hexagonal => &HEX_ODD_ROW_COORDINATES,
+        }
+    }
+
+    /// Maximum number of neighbors of a tile
+    pub fn max_neighbors(self) -> usize {
+        match self {
+            Self::Square => SQUARE_COORDINATES.len(),
+            Self::Hexagonal => HEX_EVEN_ROW_COORDINATES.len(),
+        }
+    }
+}
@@ -5,6 +5,14 @@ use crate::input_value::InputValue;
 #[derive(Component)]
 pub struct SettingsUIRoot;
 
+/// Error shown next to the input of the option it is about
+#[derive(Component)]
+pub struct FieldError;
+
+/// Row of the difficulty preset buttons
+#[derive(Component)]
+pub struct PresetRow;
+
 #[derive(Component)]
 pub enum SettingsButtonAction {
     Start,
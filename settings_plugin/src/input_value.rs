@@ -15,35 +15,35 @@ pub enum InputValue {
 
 #[derive(Debug)]
 pub enum InputError {
-    ParseFloatError(ParseFloatError),
-    ParseIntError(ParseIntError),
-    ParseBoolError(ParseBoolError),
+    Float(ParseFloatError),
+    Int(ParseIntError),
+    Bool(ParseBoolError),
 }
 
 impl From<ParseFloatError> for InputError {
     fn from(value: ParseFloatError) -> Self {
-        InputError::ParseFloatError(value)
+        InputError::Float(value)
     }
 }
 
 impl From<ParseIntError> for InputError {
     fn from(value: ParseIntError) -> Self {
-        InputError::ParseIntError(value)
+        InputError::Int(value)
     }
 }
 
 impl From<ParseBoolError> for InputError {
     fn from(value: ParseBoolError) -> Self {
-        InputError::ParseBoolError(value)
+        InputError::Bool(value)
     }
 }
 
 impl fmt::Display for InputError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            InputError::ParseFloatError(e) => write!(f, "{}", e),
-            InputError::ParseIntError(e) => write!(f, "{}", e),
-            InputError::ParseBoolError(e) => write!(f, "{}", e),
+            InputError::Float(e) => write!(f, "{}", e),
+            InputError::Int(e) => write!(f, "{}", e),
+            InputError::Bool(e) => write!(f, "{}", e),
         }
     }
 }
@@ -87,9 +87,9 @@ impl From<String> for InputValue {
     }
 }
 
-impl Into<String> for InputValue {
-    fn into(self) -> String {
-        self.as_string()
+impl From<InputValue> for String {
+    fn from(value: InputValue) -> Self {
+        value.as_string()
     }
 }
 
@@ -5,7 +5,7 @@ pub mod resources;
 mod ui;
 
 use bevy::{
-    color::palettes::css::{DARK_GRAY, GRAY},
+    asset::{AssetLoadFailedEvent, LoadedFolder, RecursiveDependencyLoadState},
     log,
     prelude::*,
 };
@@ -14,10 +14,11 @@ use std::fs;
 
 use components::SettingsUIRoot;
 use events::{BackToMenuEvent, CreateGameEvent};
-use resources::{BoardAssets, BoardOptions, SpriteMaterial};
+use resources::{BoardOptions, DEFAULT_THEME, Profiles, SettingsError, Theme, ThemeLoader, Themes};
 use ui::{
     common::{change_background_color, in_focus_cursor, keyboard_handler, menu_action},
-    root::create_menu,
+    preset_row::{show_preset, show_preset_values},
+    root::{create_menu, reload_menu},
 };
 
 pub struct SettingsPlugin<T> {
@@ -26,77 +27,187 @@ pub struct SettingsPlugin<T> {
 
 impl<T: States> Plugin for SettingsPlugin<T> {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            OnEnter(self.running_state.clone()),
-            (Self::setup_board, create_menu).chain(),
-        )
-        .add_systems(
-            Update,
-            (
-                (change_background_color, menu_action).chain(),
-                keyboard_handler,
-                in_focus_cursor,
+        // Board assets are needed even when a saved game skips the settings
+        app.init_asset::<Theme>()
+            .init_asset_loader::<ThemeLoader>()
+            .add_systems(Startup, Self::setup_assets)
+            .add_systems(
+                OnEnter(self.running_state.clone()),
+                (Self::setup_board, create_menu).chain(),
             )
-                .run_if(in_state(self.running_state.clone())),
-        )
-        .add_systems(OnExit(self.running_state.clone()), Self::cleanup_menu);
+            .add_systems(
+                Update,
+                (
+                    (change_background_color, menu_action).chain(),
+                    keyboard_handler,
+                    in_focus_cursor,
+                    // Presets and the fields they fill follow each other
+                    (show_preset, show_preset_values).run_if(resource_changed::<BoardOptions>),
+                )
+                    .run_if(in_state(self.running_state.clone())),
+            )
+            .add_systems(
+                Update,
+                (
+                    Self::select_theme.run_if(resource_exists_and_changed::<BoardOptions>),
+                    Self::apply_theme,
+                    Self::list_themes,
+                )
+                    .chain(),
+            )
+            .add_systems(OnExit(self.running_state.clone()), Self::cleanup_menu);
         app.add_message::<CreateGameEvent>();
         app.add_message::<BackToMenuEvent>();
     }
 }
 
 impl<T> SettingsPlugin<T> {
-    fn setup_board(mut commands: Commands, asset_server: Res<AssetServer>) {
-        // Board plugin options
-        let board_options: BoardOptions = fs::read_to_string("board_options.ron")
-            .map(|s| ron::from_str(&s).unwrap())
-            .unwrap();
+    fn setup_board(mut commands: Commands) {
+        // Board plugin options, from the last used profile
+        let profiles = Profiles::load();
+        let board_options = Profiles::read_or_default(&profiles.current, &mut commands);
 
-        log::info!("{:?}", board_options);
+        log::info!("Profile {}: {:?}", profiles.current, board_options);
 
         commands.insert_resource(board_options);
+        commands.insert_resource(profiles);
+    }
+
+    fn setup_assets(mut commands: Commands, asset_server: Res<AssetServer>) {
+        // The board assets come from the theme of the last used profile, once it is loaded
+        let theme = Profiles::read(&Profiles::load().current)
+            .ok()
+            .flatten()
+            .map_or_else(|| DEFAULT_THEME.to_string(), |options| options.theme);
+        commands.insert_resource(Themes::load(&theme, &asset_server));
+    }
+
+    /// Loads the theme picked in the board options
+    fn select_theme(
+        board_options: Res<BoardOptions>,
+        mut themes: ResMut<Themes>,
+        asset_server: Res<AssetServer>,
+    ) {
+        if themes.current != board_options.theme {
+            themes.select(&board_options.theme, &asset_server);
+        }
+    }
 
-        // Board assets
-        commands.insert_resource(BoardAssets {
-            label: "Default".to_string(),
-            board_material: SpriteMaterial {
-                color: Color::WHITE,
-                ..default()
-            },
-            tile_material: SpriteMaterial {
-                color: Color::from(DARK_GRAY),
-                ..default()
-            },
-            covered_tile_material: SpriteMaterial {
-                color: Color::from(GRAY),
-                ..default()
-            },
-            bomb_counter_font: asset_server.load("fonts/pixeled.ttf"),
-            bomb_counter_colors: BoardAssets::default_colors(),
-            flag_material: SpriteMaterial {
-                texture: asset_server.load("sprites/flag.png"),
-                color: Color::WHITE,
-            },
-            bomb_material: SpriteMaterial {
-                texture: asset_server.load("sprites/bomb.png"),
-                color: Color::WHITE,
-            },
-        });
+    /// Replaces the board assets by the current theme once it is loaded with its textures and
+    /// font, and whenever its file changes. Themes which can't be loaded are reported in the
+    /// settings error banner, the board assets staying as they were
+    fn apply_theme(
+        mut events: MessageReader<AssetEvent<Theme>>,
+        mut failures: MessageReader<AssetLoadFailedEvent<Theme>>,
+        mut themes: ResMut<Themes>,
+        theme_assets: Res<Assets<Theme>>,
+        asset_server: Res<AssetServer>,
+        roots: Query<(), With<SettingsUIRoot>>,
+        mut commands: Commands,
+    ) {
+        // All the messages are read, so they aren't seen again next frame
+        let modified = events
+            .read()
+            .filter(|event| event.is_modified(&themes.handle))
+            .count()
+            > 0;
+        // An edited theme file which became invalid keeps its last loaded version
+        let reload_error = failures
+            .read()
+            .filter(|failure| failure.id == themes.handle.id())
+            .last()
+            .map(|failure| failure.error.to_string());
+
+        let error = if themes.loading {
+            match asset_server.recursive_dependency_load_state(&themes.handle) {
+                RecursiveDependencyLoadState::Loaded => None,
+                RecursiveDependencyLoadState::Failed(error) => Some(error.to_string()),
+                _ => return,
+            }
+        } else if let Some(error) = reload_error {
+            Some(error)
+        } else if modified {
+            None
+        } else {
+            return;
+        };
+        themes.loading = false;
+
+        if let Some(error) = error {
+            let message = format!("Theme {} can't be loaded: {}", themes.current, error);
+            log::error!("{}", message);
+            commands.insert_resource(SettingsError(message));
+            // An open settings menu shows the error at once
+            if !roots.is_empty() {
+                commands.run_system_cached(reload_menu);
+            }
+            return;
+        }
+        if let Some(theme) = theme_assets.get(&themes.handle) {
+            log::info!("Theme {} applied", themes.current);
+            commands.insert_resource(theme.assets.clone());
+        }
+    }
+
+    /// Lists the theme packs once their folder is loaded, and again when a theme is loaded or
+    /// changes its label
+    fn list_themes(
+        mut folder_events: MessageReader<AssetEvent<LoadedFolder>>,
+        mut theme_events: MessageReader<AssetEvent<Theme>>,
+        mut themes: ResMut<Themes>,
+        folders: Res<Assets<LoadedFolder>>,
+        theme_assets: Res<Assets<Theme>>,
+        roots: Query<(), With<SettingsUIRoot>>,
+        mut commands: Commands,
+    ) {
+        // All the messages are read, so they aren't seen again next frame
+        let folder_changed = folder_events
+            .read()
+            .filter(|event| {
+                event.is_loaded_with_dependencies(&themes.folder)
+                    || event.is_added(&themes.folder)
+                    || event.is_modified(&themes.folder)
+            })
+            .count()
+            > 0;
+        let theme_changed = theme_events
+            .read()
+            .filter(|event| {
+                matches!(
+                    event,
+                    AssetEvent::LoadedWithDependencies { .. } | AssetEvent::Modified { .. }
+                )
+            })
+            .count()
+            > 0;
+        if !folder_changed && !theme_changed {
+            return;
+        }
+        let Some(folder) = folders.get(&themes.folder) else {
+            return;
+        };
+        // An open settings menu shows the new list at once
+        if themes.list(folder, &theme_assets) && !roots.is_empty() {
+            commands.run_system_cached(reload_menu);
+        }
     }
 
     fn cleanup_menu(
         mut commands: Commands,
         query: Query<Entity, With<SettingsUIRoot>>,
         board_options: Res<BoardOptions>,
+        profiles: Res<Profiles>,
     ) {
         let entity = query.single().unwrap();
         commands.entity(entity).despawn();
         log::info!("Settings menu closed");
 
-        fs::write(
-            "board_options.ron",
-            to_string_pretty(&board_options.into_inner(), PrettyConfig::default()).unwrap(),
-        )
-        .expect("Error saving settings");
+        commands.remove_resource::<SettingsError>();
+
+        let path = profiles.current_path();
+        let raw = to_string_pretty(&board_options.into_inner(), PrettyConfig::default()).unwrap();
+        if let Err(e) = fs::write(&path, raw) {
+            log::error!("Error saving settings to {}: {}", path.display(), e);
+        }
     }
 }
@@ -34,19 +34,21 @@ use bevy_inspector_egui::prelude::ReflectInspectorOptions;
 pub struct BoardAssets {
     /// Label
     pub label: String,
-    ///
+    /// Background of the board
     pub board_material: SpriteMaterial,
-    ///
+    /// Uncovered tiles
     pub tile_material: SpriteMaterial,
-    ///
+    /// Tile covers
     pub covered_tile_material: SpriteMaterial,
-    ///
+    /// Font of the bomb neighbor counts
     pub bomb_counter_font: Handle<Font>,
-    ///
+    /// Colors of the bomb neighbor counts, from 1 bomb up
     pub bomb_counter_colors: Vec<Color>,
-    ///
+    /// Flag marks
     pub flag_material: SpriteMaterial,
-    ///
+    /// Question mark, when enabled in the board options
+    pub question_material: SpriteMaterial,
+    /// Bombs, seen once uncovered
     pub bomb_material: SpriteMaterial,
 }
 
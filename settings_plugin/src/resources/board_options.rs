@@ -1,6 +1,11 @@
+use std::{error::Error, fmt};
+
 use bevy::prelude::{Resource, Vec3};
+use minefield::NO_GUESS_MAX_AREA;
 use serde::{Deserialize, Serialize};
 
+use super::DEFAULT_THEME;
+
 /// Tile size options
 #[cfg_attr(feature = "debug", derive(bevy::reflect::Reflect))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +43,59 @@ impl Default for BoardPosition {
     }
 }
 
+/// Bomb layout generation mode
+#[cfg_attr(feature = "debug", derive(bevy::reflect::Reflect))]
+#[derive(
+    Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize,
+)]
+pub enum BoardGeneration {
+    /// Bombs are placed anywhere, the player may have to guess
+    #[default]
+    Random,
+    /// The board can always be solved from its safe start without guessing
+    NoGuess,
+}
+
+/// Shape of the board tiles
+#[cfg_attr(feature = "debug", derive(bevy::reflect::Reflect))]
+#[derive(
+    Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize,
+)]
+pub enum BoardTopology {
+    /// Square tiles with 8 neighbors
+    #[default]
+    Square,
+    /// Hexagonal tiles with 6 neighbors
+    Hexagonal,
+}
+
+/// Classic difficulty levels, or a map size and bomb count of the player's own
+#[cfg_attr(feature = "debug", derive(bevy::reflect::Reflect))]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum BoardPreset {
+    /// 9x9 tiles with 10 bombs
+    Beginner,
+    /// 16x16 tiles with 40 bombs
+    Intermediate,
+    /// 30x16 tiles with 99 bombs
+    Expert,
+    /// Map size and bomb count edited by hand
+    #[default]
+    Custom,
+}
+
+impl BoardPreset {
+    /// Map size and bomb count of the preset, custom boards keep their own
+    pub fn values(self) -> Option<((u16, u16), u32)> {
+        match self {
+            Self::Beginner => Some(((9, 9), 10)),
+            Self::Intermediate => Some(((16, 16), 40)),
+            Self::Expert => Some(((30, 16), 99)),
+            Self::Custom => None,
+        }
+    }
+}
+
 /// Board generation options. Must be used as a resource
 // We use serde to allow saving option presets and loading them at runtime
 #[cfg(feature = "debug")]
@@ -54,7 +112,7 @@ pub struct BoardOptions {
     /// Tile map size
     pub map_size: (u16, u16),
     /// bomb count
-    pub bomb_count: u16,
+    pub bomb_count: u32,
     /// Board world position
     pub position: BoardPosition,
     /// Tile world size
@@ -63,6 +121,34 @@ pub struct BoardOptions {
     pub tile_padding: f32,
     /// Does the board generate a safe place to start
     pub safe_start: bool,
+    /// Are the bombs placed after the first uncovered tile, keeping it and its neighbors safe
+    #[serde(default)]
+    pub first_click_safe: bool,
+    /// Seed of the bomb layout, a random one is picked when empty
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Bomb layout generation mode
+    #[serde(default)]
+    pub generation: BoardGeneration,
+    /// Shape of the tiles
+    #[serde(default)]
+    pub topology: BoardTopology,
+    /// Do the opposite edges of the board touch each other
+    #[serde(default)]
+    pub wrap_edges: bool,
+    /// Can covered tiles be marked with a question mark, after the flag
+    #[serde(default)]
+    pub question_marks: bool,
+    /// Difficulty preset the map size and bomb count come from
+    #[serde(default)]
+    pub preset: BoardPreset,
+    /// Theme pack the board assets come from
+    #[serde(default = "default_theme")]
+    pub theme: String,
+}
+
+fn default_theme() -> String {
+    DEFAULT_THEME.into()
 }
 
 impl Default for BoardOptions {
@@ -74,103 +160,346 @@ impl Default for BoardOptions {
             tile_size: Default::default(),
             tile_padding: 0.,
             safe_start: false,
+            first_click_safe: false,
+            seed: None,
+            generation: Default::default(),
+            topology: Default::default(),
+            wrap_edges: false,
+            question_marks: false,
+            preset: BoardPreset::Custom,
+            theme: default_theme(),
         }
     }
 }
 
-impl BoardOptions {
-    pub fn set_width(&mut self, width: u16) -> Result<(), String> {
-        let area = Self::get_area(width, self.map_size.1)?;
+/// Largest number of tiles of a board
+pub const MAX_AREA: u32 = 1_000_000;
+/// Smallest number of tiles of a board
+pub const MIN_AREA: u32 = 4;
 
-        if area < 4 {
-            return Err("Width too small!".into());
-        }
+/// Board option checked by the `BoardOptions` setters
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum BoardOptionsField {
+    Width,
+    Height,
+    BombCount,
+    Generation,
+    TilePadding,
+    TileSize,
+}
+
+/// Rejected board option value, with the field it was given for and the limit it broke
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoardOptionsError {
+    /// The map would have less than `min` tiles
+    AreaTooSmall {
+        field: BoardOptionsField,
+        value: u16,
+        area: u32,
+        min: u32,
+    },
+    /// The map would have more than `max` tiles
+    AreaTooLarge {
+        field: BoardOptionsField,
+        value: u16,
+        area: u32,
+        max: u32,
+    },
+    /// The bombs would leave no safe tile on the map
+    TooManyBombs {
+        field: BoardOptionsField,
+        value: u32,
+        bomb_count: u32,
+        area: u32,
+    },
+    /// The map would have more than `max` tiles for a no guess generation
+    NoGuessAreaTooLarge {
+        field: BoardOptionsField,
+        area: u32,
+        max: u32,
+    },
+    /// The tile padding is negative
+    NegativeTilePadding { value: f32 },
+    /// The tile padding would leave nothing of the tiles
+    TilePaddingTooLarge { value: f32, tile_size: f32 },
+    /// The fixed or largest tile size isn't above the tile padding
+    TileSizeTooSmall { value: f32, tile_padding: f32 },
+    /// The smallest adaptive tile size isn't positive
+    NonPositiveTileSize { value: f32 },
+    /// The smallest adaptive tile size isn't below the largest one
+    InvertedTileSizes { min: f32, max: f32 },
+}
 
-        if area > 25600 {
-            return Err("Width too large!".into());
+impl BoardOptionsError {
+    /// Option the rejected value was given for
+    pub fn field(&self) -> BoardOptionsField {
+        match self {
+            Self::AreaTooSmall { field, .. }
+            | Self::AreaTooLarge { field, .. }
+            | Self::TooManyBombs { field, .. }
+            | Self::NoGuessAreaTooLarge { field, .. } => *field,
+            Self::NegativeTilePadding { .. } | Self::TilePaddingTooLarge { .. } => {
+                BoardOptionsField::TilePadding
+            }
+            Self::TileSizeTooSmall { .. }
+            | Self::NonPositiveTileSize { .. }
+            | Self::InvertedTileSizes { .. } => BoardOptionsField::TileSize,
         }
+    }
+}
 
-        if area <= self.bomb_count {
-            return Err("The bombs don't fit area!".into());
+impl fmt::Display for BoardOptionsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AreaTooSmall {
+                field,
+                value,
+                area,
+                min,
+            } => write!(
+                f,
+                "{:?} {} gives {} tiles, at least {} are needed",
+                field, value, area, min
+            ),
+            Self::AreaTooLarge {
+                field,
+                value,
+                area,
+                max,
+            } => write!(
+                f,
+                "{:?} {} gives {} tiles, at most {} are allowed",
+                field, value, area, max
+            ),
+            Self::TooManyBombs {
+                bomb_count, area, ..
+            } => write!(f, "{} bombs don't fit {} tiles", bomb_count, area),
+            Self::NoGuessAreaTooLarge { area, max, .. } => write!(
+                f,
+                "No guess boards have at most {} tiles, not {}",
+                max, area
+            ),
+            Self::NegativeTilePadding { value } => {
+                write!(f, "Tile padding {} must be positive", value)
+            }
+            Self::TilePaddingTooLarge { value, tile_size } => write!(
+                f,
+                "Tile padding {} must be less than the tile size {}",
+                value, tile_size
+            ),
+            Self::TileSizeTooSmall {
+                value,
+                tile_padding,
+            } => write!(
+                f,
+                "Tile size {} must be more than the tile padding {}",
+                value, tile_padding
+            ),
+            Self::NonPositiveTileSize { value } => {
+                write!(f, "Min tile size {} must be positive", value)
+            }
+            Self::InvertedTileSizes { min, max } => {
+                write!(f, "Min tile size {} must be less than the max {}", min, max)
+            }
         }
+    }
+}
+
+impl Error for BoardOptionsError {}
+
+impl BoardOptions {
+    pub fn set_width(&mut self, width: u16) -> Result<(), BoardOptionsError> {
+        let area = Self::get_area(width, self.map_size.1);
+        self.check_area(BoardOptionsField::Width, width, area)?;
 
+        if width != self.map_size.0 {
+            self.preset = BoardPreset::Custom;
+        }
         self.map_size.0 = width;
         Ok(())
     }
 
-    pub fn set_height(&mut self, height: u16) -> Result<(), String> {
-        let area = Self::get_area(self.map_size.0, height)?;
+    pub fn set_height(&mut self, height: u16) -> Result<(), BoardOptionsError> {
+        let area = Self::get_area(self.map_size.0, height);
+        self.check_area(BoardOptionsField::Height, height, area)?;
 
-        if area < 4 {
-            return Err("Height too small!".into());
+        if height != self.map_size.1 {
+            self.preset = BoardPreset::Custom;
         }
+        self.map_size.1 = height;
+        Ok(())
+    }
+
+    pub fn set_bomb_count(&mut self, bombs: u32) -> Result<(), BoardOptionsError> {
+        let area = Self::get_area(self.map_size.0, self.map_size.1);
 
-        if area > 25600 {
-            return Err("Height too large!".into());
+        if bombs >= area {
+            return Err(BoardOptionsError::TooManyBombs {
+                field: BoardOptionsField::BombCount,
+                value: bombs,
+                bomb_count: bombs,
+                area,
+            });
         }
 
-        if area <= self.bomb_count {
-            return Err("The bombs don't fit area!".into());
+        if bombs != self.bomb_count {
+            self.preset = BoardPreset::Custom;
         }
+        self.bomb_count = bombs;
+        Ok(())
+    }
 
-        self.map_size.1 = height;
+    /// Sets the generation, no guess boards being limited to `NO_GUESS_MAX_AREA` tiles
+    pub fn set_generation(&mut self, generation: BoardGeneration) -> Result<(), BoardOptionsError> {
+        let area = Self::get_area(self.map_size.0, self.map_size.1);
+        Self::check_generation(BoardOptionsField::Generation, generation, area)?;
+
+        self.generation = generation;
         Ok(())
     }
 
-    pub fn set_bomb_count(&mut self, bombs: u16) -> Result<(), String> {
-        let area = Self::get_area(self.map_size.0, self.map_size.1)?;
+    /// Checks a whole configuration with the setters rules, like loaded options which didn't go
+    /// through them, returning every broken rule
+    pub fn validate(&self) -> Result<(), Vec<BoardOptionsError>> {
+        // Bombs, generation and padding are checked once the sizes they depend on are known
+        let mut checked = Self {
+            bomb_count: 0,
+            generation: BoardGeneration::Random,
+            tile_padding: 0.,
+            ..self.clone()
+        };
+        // Both sizes give a single area, broken limits are reported once, on the width
+        let (width, height) = self.map_size;
+        let area = Self::get_area(width, height);
+        let errors: Vec<BoardOptionsError> = [
+            checked.check_area(BoardOptionsField::Width, width, area),
+            checked.set_bomb_count(self.bomb_count),
+            checked.set_generation(self.generation),
+            checked.set_tile_size(self.tile_size.clone()),
+            checked.set_tile_padding(self.tile_padding),
+        ]
+        .into_iter()
+        .filter_map(Result::err)
+        .collect();
 
-        if bombs >= area {
-            return Err("Too many bombs!".into());
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
         }
+    }
 
-        self.bomb_count = bombs;
-        Ok(())
+    /// Takes the map size and bomb count of the `preset`
+    pub fn set_preset(&mut self, preset: BoardPreset) {
+        if let Some((map_size, bomb_count)) = preset.values() {
+            self.map_size = map_size;
+            self.bomb_count = bomb_count;
+        }
+        self.preset = preset;
     }
 
-    fn get_area(width: u16, height: u16) -> Result<u16, String> {
-        width.checked_mul(height).ok_or("Area is too big!".into())
+    /// Number of tiles, which can't overflow as `u32`
+    fn get_area(width: u16, height: u16) -> u32 {
+        width as u32 * height as u32
     }
 
-    pub fn set_tile_padding(&mut self, tile_padding: f32) -> Result<(), String> {
+    /// Checks the area a new width or height `value` gives
+    fn check_area(
+        &self,
+        field: BoardOptionsField,
+        value: u16,
+        area: u32,
+    ) -> Result<(), BoardOptionsError> {
+        if area < MIN_AREA {
+            return Err(BoardOptionsError::AreaTooSmall {
+                field,
+                value,
+                area,
+                min: MIN_AREA,
+            });
+        }
+
+        if area > MAX_AREA {
+            return Err(BoardOptionsError::AreaTooLarge {
+                field,
+                value,
+                area,
+                max: MAX_AREA,
+            });
+        }
+
+        if area <= self.bomb_count {
+            return Err(BoardOptionsError::TooManyBombs {
+                field,
+                value: value.into(),
+                bomb_count: self.bomb_count,
+                area,
+            });
+        }
+        Self::check_generation(field, self.generation, area)
+    }
+
+    /// Checks that a no guess board of `area` tiles can be generated
+    fn check_generation(
+        field: BoardOptionsField,
+        generation: BoardGeneration,
+        area: u32,
+    ) -> Result<(), BoardOptionsError> {
+        if generation == BoardGeneration::NoGuess && area as usize > NO_GUESS_MAX_AREA {
+            return Err(BoardOptionsError::NoGuessAreaTooLarge {
+                field,
+                area,
+                max: NO_GUESS_MAX_AREA as u32,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn set_tile_padding(&mut self, tile_padding: f32) -> Result<(), BoardOptionsError> {
         if tile_padding < 0.0 {
-            return Err("Tile padding must be positive!".into());
+            return Err(BoardOptionsError::NegativeTilePadding {
+                value: tile_padding,
+            });
         }
-        match self.tile_size {
-            TileSize::Fixed(v) => {
-                if tile_padding >= v {
-                    return Err("Tile padding is too big!".into());
-                }
-            }
-            TileSize::Adaptive { min: _, max } => {
-                if tile_padding >= max {
-                    return Err("Tile padding is too big!".into());
-                }
-            }
+        let tile_size = match self.tile_size {
+            TileSize::Fixed(v) => v,
+            TileSize::Adaptive { min: _, max } => max,
+        };
+        if tile_padding >= tile_size {
+            return Err(BoardOptionsError::TilePaddingTooLarge {
+                value: tile_padding,
+                tile_size,
+            });
         }
 
         self.tile_padding = tile_padding;
         Ok(())
     }
 
-    pub fn set_tile_size(&mut self, tile_size: TileSize) -> Result<(), String> {
+    pub fn set_tile_size(&mut self, tile_size: TileSize) -> Result<(), BoardOptionsError> {
         match tile_size {
             TileSize::Fixed(v) => {
                 if v <= self.tile_padding {
-                    return Err("Fixed value is too small!".into());
+                    return Err(BoardOptionsError::TileSizeTooSmall {
+                        value: v,
+                        tile_padding: self.tile_padding,
+                    });
                 }
             }
             TileSize::Adaptive { min, max } => {
                 if min >= max {
-                    return Err("Min value must be less than max".into());
+                    return Err(BoardOptionsError::InvertedTileSizes { min, max });
                 }
 
                 if max <= self.tile_padding {
-                    return Err("Max value is too small!".into());
+                    return Err(BoardOptionsError::TileSizeTooSmall {
+                        value: max,
+                        tile_padding: self.tile_padding,
+                    });
                 }
 
                 if min <= 0.0 {
-                    return Err("Min value must be positive!".into());
+                    return Err(BoardOptionsError::NonPositiveTileSize { value: min });
                 }
             }
         }
@@ -179,3 +508,203 @@ impl BoardOptions {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(map_size: (u16, u16), bomb_count: u32) -> BoardOptions {
+        BoardOptions {
+            map_size,
+            bomb_count,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sizes_keep_the_area_within_limits() {
+        let mut board = options((10, 10), 5);
+
+        let error = board.set_width(0).unwrap_err();
+        assert_eq!(
+            error,
+            BoardOptionsError::AreaTooSmall {
+                field: BoardOptionsField::Width,
+                value: 0,
+                area: 0,
+                min: MIN_AREA,
+            }
+        );
+        assert_eq!(error.field(), BoardOptionsField::Width);
+
+        let mut board = options((100, 10), 5);
+        let error = board.set_height(20_000).unwrap_err();
+        assert_eq!(
+            error,
+            BoardOptionsError::AreaTooLarge {
+                field: BoardOptionsField::Height,
+                value: 20_000,
+                area: 2_000_000,
+                max: MAX_AREA,
+            }
+        );
+        assert_eq!(error.field(), BoardOptionsField::Height);
+        // Rejected sizes are left as they were
+        assert_eq!(board.map_size, (100, 10));
+    }
+
+    #[test]
+    fn bombs_leave_a_safe_tile() {
+        let mut board = options((10, 10), 5);
+
+        let error = board.set_bomb_count(100).unwrap_err();
+        assert_eq!(
+            error,
+            BoardOptionsError::TooManyBombs {
+                field: BoardOptionsField::BombCount,
+                value: 100,
+                bomb_count: 100,
+                area: 100,
+            }
+        );
+        assert_eq!(error.field(), BoardOptionsField::BombCount);
+
+        // Shrinking the map is checked against the bombs
+        let mut board = options((10, 10), 50);
+        let error = board.set_width(5).unwrap_err();
+        assert_eq!(
+            error,
+            BoardOptionsError::TooManyBombs {
+                field: BoardOptionsField::Width,
+                value: 5,
+                bomb_count: 50,
+                area: 50,
+            }
+        );
+        assert_eq!(error.field(), BoardOptionsField::Width);
+        assert_eq!(board.set_bomb_count(99), Ok(()));
+    }
+
+    #[test]
+    fn no_guess_boards_are_limited_in_area() {
+        let mut board = options((60, 60), 10);
+
+        let error = board.set_generation(BoardGeneration::NoGuess).unwrap_err();
+        assert_eq!(
+            error,
+            BoardOptionsError::NoGuessAreaTooLarge {
+                field: BoardOptionsField::Generation,
+                area: 3_600,
+                max: NO_GUESS_MAX_AREA as u32,
+            }
+        );
+        assert_eq!(error.field(), BoardOptionsField::Generation);
+        assert_eq!(board.generation, BoardGeneration::Random);
+
+        // Growing a no guess map is checked against its generation
+        let mut board = options((50, 50), 10);
+        board.set_generation(BoardGeneration::NoGuess).unwrap();
+        let error = board.set_height(51).unwrap_err();
+        assert_eq!(
+            error,
+            BoardOptionsError::NoGuessAreaTooLarge {
+                field: BoardOptionsField::Height,
+                area: 2_550,
+                max: NO_GUESS_MAX_AREA as u32,
+            }
+        );
+        assert_eq!(error.field(), BoardOptionsField::Height);
+    }
+
+    #[test]
+    fn tile_padding_fits_in_the_tiles() {
+        let mut board = BoardOptions {
+            tile_size: TileSize::Fixed(20.),
+            ..Default::default()
+        };
+
+        let error = board.set_tile_padding(-1.).unwrap_err();
+        assert_eq!(error, BoardOptionsError::NegativeTilePadding { value: -1. });
+        assert_eq!(error.field(), BoardOptionsField::TilePadding);
+
+        let error = board.set_tile_padding(20.).unwrap_err();
+        assert_eq!(
+            error,
+            BoardOptionsError::TilePaddingTooLarge {
+                value: 20.,
+                tile_size: 20.,
+            }
+        );
+        assert_eq!(error.field(), BoardOptionsField::TilePadding);
+        assert_eq!(board.set_tile_padding(2.), Ok(()));
+    }
+
+    #[test]
+    fn tile_sizes_are_ordered_and_above_the_padding() {
+        let mut board = BoardOptions {
+            tile_padding: 5.,
+            ..Default::default()
+        };
+
+        let error = board.set_tile_size(TileSize::Fixed(5.)).unwrap_err();
+        assert_eq!(
+            error,
+            BoardOptionsError::TileSizeTooSmall {
+                value: 5.,
+                tile_padding: 5.,
+            }
+        );
+        assert_eq!(error.field(), BoardOptionsField::TileSize);
+
+        let error = board
+            .set_tile_size(TileSize::Adaptive { min: 0., max: 50. })
+            .unwrap_err();
+        assert_eq!(error, BoardOptionsError::NonPositiveTileSize { value: 0. });
+        assert_eq!(error.field(), BoardOptionsField::TileSize);
+
+        let error = board
+            .set_tile_size(TileSize::Adaptive { min: 50., max: 10. })
+            .unwrap_err();
+        assert_eq!(
+            error,
+            BoardOptionsError::InvertedTileSizes { min: 50., max: 10. }
+        );
+        assert_eq!(error.field(), BoardOptionsField::TileSize);
+    }
+
+    #[test]
+    fn validate_reports_an_oversized_area_once() {
+        let board = options((2_000, 1_000), 10);
+
+        assert_eq!(
+            board.validate(),
+            Err(vec![BoardOptionsError::AreaTooLarge {
+                field: BoardOptionsField::Width,
+                value: 2_000,
+                area: 2_000_000,
+                max: MAX_AREA,
+            }])
+        );
+    }
+
+    #[test]
+    fn validate_returns_every_broken_rule() {
+        let board = BoardOptions {
+            bomb_count: 300,
+            tile_padding: -1.,
+            ..Default::default()
+        };
+
+        let fields: Vec<BoardOptionsField> = board
+            .validate()
+            .unwrap_err()
+            .iter()
+            .map(BoardOptionsError::field)
+            .collect();
+        assert_eq!(
+            fields,
+            [BoardOptionsField::BombCount, BoardOptionsField::TilePadding]
+        );
+        assert_eq!(BoardOptions::default().validate(), Ok(()));
+    }
+}
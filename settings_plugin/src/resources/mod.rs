@@ -1,5 +1,17 @@
 pub use board_assets::*;
 pub use board_options::*;
+pub use profiles::*;
+pub use replay::*;
+pub use saved_game::*;
+pub use settings_error::*;
+pub use statistics::*;
+pub use theme::*;
 
 mod board_assets;
 mod board_options;
+mod profiles;
+mod replay;
+mod saved_game;
+mod settings_error;
+mod statistics;
+mod theme;
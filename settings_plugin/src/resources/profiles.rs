@@ -0,0 +1,247 @@
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    log,
+    prelude::{Commands, Resource},
+};
+use ron::ser::{PrettyConfig, to_string_pretty};
+
+use super::{BoardOptions, SettingsError};
+
+/// Profile kept in `board_options.ron`, which can't be deleted
+pub const DEFAULT_PROFILE: &str = "Default";
+/// File of the default profile
+pub const DEFAULT_PROFILE_PATH: &str = "board_options.ron";
+/// Directory the other profiles are saved in, one RON file per profile
+pub const PROFILES_DIR: &str = "profiles";
+/// File remembering the last used profile
+pub const LAST_PROFILE_PATH: &str = "last_profile.ron";
+
+/// Named board options, the current ones being loaded in the settings and saved on leaving them
+#[derive(Debug, Clone, Resource)]
+pub struct Profiles {
+    /// Profile names, the default one first
+    pub names: Vec<String>,
+    /// Profile the board options are loaded from and saved to
+    pub current: String,
+}
+
+impl Profiles {
+    /// Lists the saved profiles, the last used one being current
+    pub fn load() -> Self {
+        let mut names: Vec<String> = fs::read_dir(PROFILES_DIR)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+            .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+            .collect();
+        names.sort();
+        names.insert(0, DEFAULT_PROFILE.into());
+
+        // A profile deleted by hand falls back to the default one
+        let current = fs::read_to_string(LAST_PROFILE_PATH)
+            .ok()
+            .and_then(|raw| ron::from_str::<String>(&raw).ok())
+            .filter(|name| names.contains(name))
+            .unwrap_or_else(|| DEFAULT_PROFILE.into());
+
+        Self { names, current }
+    }
+
+    /// File of the profile called `name`
+    pub fn path(name: &str) -> PathBuf {
+        if name == DEFAULT_PROFILE {
+            PathBuf::from(DEFAULT_PROFILE_PATH)
+        } else {
+            Path::new(PROFILES_DIR).join(format!("{}.ron", name))
+        }
+    }
+
+    /// File of the current profile
+    pub fn current_path(&self) -> PathBuf {
+        Self::path(&self.current)
+    }
+
+    /// Reads the board options of the profile called `name`, `None` if it has no file yet.
+    ///
+    /// Invalid options are backed up next to their file, which is overwritten when the settings
+    /// are left
+    pub fn read(name: &str) -> Result<Option<BoardOptions>, String> {
+        let path = Self::path(name);
+        let raw = match fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Can't read {}: {}", path.display(), e)),
+        };
+        let error = match ron::from_str::<BoardOptions>(&raw) {
+            Ok(options) => match options.validate() {
+                Ok(()) => return Ok(Some(options)),
+                Err(errors) => errors
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            },
+            Err(e) => e.to_string(),
+        };
+
+        let backup = path.with_extension("ron.bak");
+        match fs::write(&backup, raw) {
+            Ok(()) => Err(format!(
+                "Invalid {}: {}. It was backed up to {}",
+                path.display(),
+                error,
+                backup.display()
+            )),
+            Err(e) => Err(format!(
+                "Invalid {}: {}. It couldn't be backed up: {}",
+                path.display(),
+                error,
+                e
+            )),
+        }
+    }
+
+    /// Reads the board options of the profile called `name`, the default ones standing in for
+    /// missing or invalid options
+    pub fn read_or_default(name: &str, commands: &mut Commands) -> BoardOptions {
+        match Self::read(name) {
+            Ok(Some(options)) => options,
+            Ok(None) => {
+                log::info!("No options for profile {}, using the default ones", name);
+                BoardOptions::default()
+            }
+            Err(err) => {
+                log::error!("{}", err);
+                commands.insert_resource(SettingsError(err));
+                BoardOptions::default()
+            }
+        }
+    }
+
+    /// Makes the profile called `name` current, remembering it for the next launch
+    pub fn select(&mut self, name: &str) {
+        self.current = name.into();
+        let raw = ron::to_string(&self.current).unwrap();
+        if let Err(e) = fs::write(LAST_PROFILE_PATH, raw) {
+            log::error!("Error saving the last used profile: {}", e);
+        }
+    }
+
+    /// Saves `options` as the profile called `name`, which becomes current
+    pub fn save_as(&mut self, name: &str, options: &BoardOptions) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Profile name is empty!".into());
+        }
+        // Names are file names
+        if !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+        {
+            return Err("Profile names only use letters, digits, spaces, - and _!".into());
+        }
+
+        let path = Self::path(name);
+        let raw = to_string_pretty(options, PrettyConfig::default()).unwrap();
+        fs::create_dir_all(PROFILES_DIR)
+            .and_then(|_| fs::write(&path, raw))
+            .map_err(|e| format!("Error saving profile {}: {}", path.display(), e))?;
+        log::info!("Profile saved to {}", path.display());
+
+        if !self.names.iter().any(|n| n == name) {
+            self.names.push(name.into());
+            self.names[1..].sort();
+        }
+        self.select(name);
+        Ok(())
+    }
+
+    /// Deletes the current profile, the default one becoming current
+    pub fn delete_current(&mut self) -> Result<(), String> {
+        if self.current == DEFAULT_PROFILE {
+            return Err("The default profile can't be deleted!".into());
+        }
+
+        let path = self.current_path();
+        fs::remove_file(&path)
+            .map_err(|e| format!("Error deleting profile {}: {}", path.display(), e))?;
+        log::info!("Profile {} deleted", self.current);
+
+        let current = self.current.clone();
+        self.names.retain(|name| *name != current);
+        self.select(DEFAULT_PROFILE);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use bevy::{ecs::world::CommandQueue, prelude::World};
+
+    use super::*;
+
+    /// Reads the profile called `name` with the defaults standing in, returning them with the
+    /// reported error
+    fn read_or_default(name: &str) -> (BoardOptions, Option<String>) {
+        let mut world = World::new();
+        let mut queue = CommandQueue::default();
+        let options = Profiles::read_or_default(name, &mut Commands::new(&mut queue, &world));
+        queue.apply(&mut world);
+        let error = world.remove_resource::<SettingsError>().map(|e| e.0);
+        (options, error)
+    }
+
+    // The only test using files, profiles being read from the working directory
+    #[test]
+    fn invalid_profiles_fall_back_to_defaults_and_are_backed_up() {
+        let dir = env::temp_dir().join(format!("settings_profiles_{}", std::process::id()));
+        fs::create_dir_all(dir.join(PROFILES_DIR)).unwrap();
+        let previous_dir = env::current_dir().unwrap();
+        env::set_current_dir(&dir).unwrap();
+
+        // Missing profiles use the defaults without error
+        assert!(matches!(Profiles::read("Missing"), Ok(None)));
+        let (options, error) = read_or_default("Missing");
+        assert_eq!(options.map_size, BoardOptions::default().map_size);
+        assert_eq!(error, None);
+
+        // Corrupt and invalid profiles are kept in a backup
+        let invalid = to_string_pretty(
+            &BoardOptions {
+                bomb_count: 1_000,
+                ..Default::default()
+            },
+            PrettyConfig::default(),
+        )
+        .unwrap();
+        for (name, raw) in [
+            ("Corrupt", "(map_size: (10,"),
+            ("Invalid", invalid.as_str()),
+        ] {
+            let path = Profiles::path(name);
+            fs::write(&path, raw).unwrap();
+
+            let (options, error) = read_or_default(name);
+            assert_eq!(options.map_size, BoardOptions::default().map_size);
+            assert_eq!(options.bomb_count, BoardOptions::default().bomb_count);
+            assert!(error.unwrap().contains("backed up"), "{}", name);
+            assert_eq!(
+                fs::read_to_string(path.with_extension("ron.bak")).unwrap(),
+                raw
+            );
+            // The profile itself is only overwritten when the settings are left
+            assert_eq!(fs::read_to_string(&path).unwrap(), raw);
+        }
+
+        env::set_current_dir(previous_dir).unwrap();
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,87 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::{log, prelude::Resource};
+use ron::ser::{PrettyConfig, to_string_pretty};
+use serde::{Deserialize, Serialize};
+
+use super::BoardOptions;
+
+/// Directory the replays are saved in, next to `board_options.ron`
+pub const REPLAYS_DIR: &str = "replays";
+
+/// Player action, applied to the tile at some coordinates
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ReplayActionKind {
+    /// Uncover a covered tile
+    Uncover,
+    /// Set or remove a flag
+    Mark(bool),
+    /// Set or remove a question mark
+    QuestionMark(bool),
+    /// Uncover the neighbors of a bomb counter
+    Chord,
+}
+
+/// Timestamped player action
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayAction {
+    /// Game time in seconds
+    pub time: f32,
+    pub coordinates: (u16, u16),
+    pub kind: ReplayActionKind,
+}
+
+/// Recorded game, inserted as a resource to play it back
+#[derive(Debug, Clone, Serialize, Deserialize, Resource)]
+pub struct Replay {
+    /// Options of the recorded board, its seed always set
+    pub options: BoardOptions,
+    /// Did the recorded game end in a win
+    pub won: bool,
+    pub actions: Vec<ReplayAction>,
+}
+
+impl Replay {
+    /// Saved replays, most recent first
+    pub fn list() -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(REPLAYS_DIR) else {
+            return Vec::new();
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+            .collect();
+        paths.sort_by(|a, b| b.cmp(a));
+        paths
+    }
+
+    pub fn load(path: &Path) -> Option<Self> {
+        let raw = fs::read_to_string(path).ok()?;
+        ron::from_str(&raw)
+            .inspect_err(|e| log::error!("Invalid replay {}: {}", path.display(), e))
+            .ok()
+    }
+
+    /// Writes the replay in a new file named after the current time and the board seed
+    pub fn save(&self) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        let path = Path::new(REPLAYS_DIR).join(format!(
+            "{}_{}.ron",
+            timestamp,
+            self.options.seed.unwrap_or_default()
+        ));
+
+        let raw = to_string_pretty(self, PrettyConfig::default()).unwrap();
+        match fs::create_dir_all(REPLAYS_DIR).and_then(|_| fs::write(&path, raw)) {
+            Ok(_) => log::info!("Replay saved to {}", path.display()),
+            Err(e) => log::error!("Error saving replay: {}", e),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use std::fs;
+
+use bevy::{log, prelude::Resource};
+use ron::ser::{PrettyConfig, to_string_pretty};
+use serde::{Deserialize, Serialize};
+
+use super::{BoardOptions, ReplayAction};
+
+/// File the in-progress game is saved in, next to `board_options.ron`
+pub const SAVED_GAME_PATH: &str = "saved_game.ron";
+
+/// In-progress game, inserted as a resource to rebuild the board from it
+#[derive(Debug, Clone, Serialize, Deserialize, Resource)]
+pub struct SavedGame {
+    /// Options the board was created with
+    pub options: BoardOptions,
+    /// Seed of the bomb layout
+    pub seed: u64,
+    /// Bomb positions
+    pub bombs: Vec<(u16, u16)>,
+    /// Uncovered tile positions
+    pub uncovered: Vec<(u16, u16)>,
+    /// Flagged tile positions
+    pub flagged: Vec<(u16, u16)>,
+    /// Question marked tile positions
+    #[serde(default)]
+    pub questioned: Vec<(u16, u16)>,
+    /// Game time in seconds
+    pub elapsed: f32,
+    /// Clicks made so far
+    #[serde(default)]
+    pub clicks: u32,
+    /// Actions recorded so far, for the replay of the resumed game
+    #[serde(default)]
+    pub actions: Option<Vec<ReplayAction>>,
+}
+
+impl SavedGame {
+    /// Is there a game to continue
+    pub fn exists() -> bool {
+        fs::exists(SAVED_GAME_PATH).unwrap_or(false)
+    }
+
+    pub fn load() -> Option<Self> {
+        let raw = fs::read_to_string(SAVED_GAME_PATH).ok()?;
+        ron::from_str(&raw)
+            .inspect_err(|e| log::error!("Invalid saved game: {}", e))
+            .ok()
+    }
+
+    pub fn save(&self) {
+        let raw = to_string_pretty(self, PrettyConfig::default()).unwrap();
+        if let Err(e) = fs::write(SAVED_GAME_PATH, raw) {
+            log::error!("Error saving game: {}", e);
+        }
+    }
+
+    /// Removes the saved game once it is resumed, a game can only be continued once
+    pub fn delete() {
+        if let Err(e) = fs::remove_file(SAVED_GAME_PATH) {
+            log::error!("Error removing saved game: {}", e);
+        }
+    }
+}
@@ -0,0 +1,5 @@
+use bevy::prelude::Resource;
+
+/// Error shown in a banner on top of the settings screen, until it is clicked
+#[derive(Debug, Clone, Resource)]
+pub struct SettingsError(pub String);
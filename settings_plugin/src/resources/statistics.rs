@@ -0,0 +1,120 @@
+use std::{collections::BTreeMap, fs};
+
+use bevy::log;
+use ron::ser::{PrettyConfig, to_string_pretty};
+use serde::{Deserialize, Serialize};
+
+use super::{BoardGeneration, BoardOptions, BoardTopology};
+
+/// File the statistics are saved in, next to `board_options.ron`
+pub const STATISTICS_PATH: &str = "statistics.ron";
+
+/// Number of best times kept for each board configuration
+pub const BEST_TIMES_LEN: usize = 5;
+
+/// Board configuration the statistics are grouped by
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct StatisticsKey {
+    pub map_size: (u16, u16),
+    pub bomb_count: u32,
+    pub safe_start: bool,
+    #[serde(default)]
+    pub topology: BoardTopology,
+    #[serde(default)]
+    pub wrap_edges: bool,
+    #[serde(default)]
+    pub generation: BoardGeneration,
+    #[serde(default)]
+    pub first_click_safe: bool,
+}
+
+impl From<&BoardOptions> for StatisticsKey {
+    fn from(options: &BoardOptions) -> Self {
+        Self {
+            map_size: options.map_size,
+            bomb_count: options.bomb_count,
+            safe_start: options.safe_start,
+            topology: options.topology,
+            wrap_edges: options.wrap_edges,
+            generation: options.generation,
+            first_click_safe: options.first_click_safe,
+        }
+    }
+}
+
+/// Results of the games played on one board configuration
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BoardStatistics {
+    pub games_played: u32,
+    pub games_won: u32,
+    /// Games won in a row, reset by a loss
+    pub win_streak: u32,
+    pub best_win_streak: u32,
+    /// Fastest wins in seconds, best first
+    pub best_times: Vec<f32>,
+}
+
+impl BoardStatistics {
+    /// Records a game, returning the rank of its time in the best times if it made it
+    pub fn record(&mut self, won: bool, time: f32) -> Option<usize> {
+        self.games_played += 1;
+        if !won {
+            self.win_streak = 0;
+            return None;
+        }
+
+        self.games_won += 1;
+        self.win_streak += 1;
+        self.best_win_streak = self.best_win_streak.max(self.win_streak);
+
+        let rank = self.best_times.partition_point(|&best| best <= time);
+        if rank >= BEST_TIMES_LEN {
+            return None;
+        }
+        self.best_times.insert(rank, time);
+        self.best_times.truncate(BEST_TIMES_LEN);
+        Some(rank)
+    }
+
+    /// Share of games won, in percent
+    pub fn win_rate(&self) -> f32 {
+        if self.games_played == 0 {
+            return 0.0;
+        }
+        self.games_won as f32 * 100.0 / self.games_played as f32
+    }
+}
+
+/// Statistics of every board configuration played
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Statistics {
+    pub boards: BTreeMap<StatisticsKey, BoardStatistics>,
+}
+
+impl Statistics {
+    /// Loads the saved statistics, starting over when there are none or they can't be read
+    pub fn load() -> Self {
+        match fs::read_to_string(STATISTICS_PATH) {
+            Ok(raw) => ron::from_str(&raw).unwrap_or_else(|e| {
+                log::error!("Invalid statistics, starting over: {}", e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) {
+        let raw = to_string_pretty(self, PrettyConfig::default()).unwrap();
+        if let Err(e) = fs::write(STATISTICS_PATH, raw) {
+            log::error!("Error saving statistics: {}", e);
+        }
+    }
+
+    /// Records a game played with `options`, see [`BoardStatistics::record`]
+    pub fn record(&mut self, options: &BoardOptions, won: bool, time: f32) -> Option<usize> {
+        self.boards
+            .entry(options.into())
+            .or_default()
+            .record(won, time)
+    }
+}
@@ -0,0 +1,224 @@
+use std::{error::Error, fmt, io};
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedFolder, io::Reader},
+    color::HexColorError,
+    prelude::*,
+};
+use ron::{error::SpannedError, extensions::Extensions};
+use serde::Deserialize;
+
+use super::{BoardAssets, SpriteMaterial};
+
+/// Directory of the theme packs, in the assets
+pub const THEMES_DIR: &str = "themes";
+/// Extension of the theme pack files
+pub const THEME_EXTENSION: &str = "theme.ron";
+/// Theme of the profiles which don't pick one
+pub const DEFAULT_THEME: &str = "default";
+
+/// Sprite material as written in a theme file
+#[derive(Debug, Clone, Deserialize)]
+struct ThemeMaterial {
+    /// Hex color, tinting the texture if there is one
+    #[serde(default = "white")]
+    color: String,
+    /// Texture path, in the assets
+    #[serde(default)]
+    texture: Option<String>,
+}
+
+fn white() -> String {
+    "#FFFFFF".into()
+}
+
+/// Theme file, mirroring `BoardAssets` with asset paths and hex colors
+#[derive(Debug, Clone, Deserialize)]
+struct ThemeFile {
+    label: String,
+    board_material: ThemeMaterial,
+    tile_material: ThemeMaterial,
+    covered_tile_material: ThemeMaterial,
+    bomb_counter_font: String,
+    bomb_counter_colors: Vec<String>,
+    flag_material: ThemeMaterial,
+    question_material: ThemeMaterial,
+    bomb_material: ThemeMaterial,
+}
+
+/// Board assets loaded from a `.theme.ron` file of the `themes` asset directory
+#[derive(Debug, Clone, Asset, TypePath)]
+pub struct Theme {
+    pub assets: BoardAssets,
+}
+
+/// Loads `Theme` assets, along with the textures and font they use
+#[derive(Debug, Default, TypePath)]
+pub struct ThemeLoader;
+
+/// Error loading a theme file
+#[derive(Debug)]
+pub enum ThemeLoaderError {
+    /// The file can't be read
+    Io(io::Error),
+    /// The file isn't a valid theme
+    Ron(SpannedError),
+    /// A color isn't a valid hex color
+    Color { color: String, error: HexColorError },
+}
+
+impl fmt::Display for ThemeLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Can't read theme: {}", e),
+            Self::Ron(e) => write!(f, "Invalid theme: {}", e),
+            Self::Color { color, error } => write!(f, "Invalid color {}: {}", color, error),
+        }
+    }
+}
+
+impl Error for ThemeLoaderError {}
+
+impl From<io::Error> for ThemeLoaderError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<SpannedError> for ThemeLoaderError {
+    fn from(e: SpannedError) -> Self {
+        Self::Ron(e)
+    }
+}
+
+impl AssetLoader for ThemeLoader {
+    type Asset = Theme;
+    type Settings = ();
+    type Error = ThemeLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Theme, ThemeLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        // Textures can be written without `Some(..)`
+        let file: ThemeFile = ron::Options::default()
+            .with_default_extension(Extensions::IMPLICIT_SOME)
+            .from_bytes(&bytes)?;
+
+        let mut material = |material: ThemeMaterial| -> Result<_, ThemeLoaderError> {
+            Ok(SpriteMaterial {
+                color: color(material.color)?,
+                texture: material
+                    .texture
+                    .map(|path| load_context.load(path))
+                    .unwrap_or_default(),
+            })
+        };
+        let assets = BoardAssets {
+            label: file.label,
+            board_material: material(file.board_material)?,
+            tile_material: material(file.tile_material)?,
+            covered_tile_material: material(file.covered_tile_material)?,
+            flag_material: material(file.flag_material)?,
+            question_material: material(file.question_material)?,
+            bomb_material: material(file.bomb_material)?,
+            bomb_counter_colors: file
+                .bomb_counter_colors
+                .into_iter()
+                .map(color)
+                .collect::<Result<_, _>>()?,
+            bomb_counter_font: load_context.load(file.bomb_counter_font),
+        };
+        Ok(Theme { assets })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[THEME_EXTENSION]
+    }
+}
+
+fn color(hex: String) -> Result<Color, ThemeLoaderError> {
+    match Srgba::hex(&hex) {
+        Ok(color) => Ok(Color::from(color)),
+        Err(error) => Err(ThemeLoaderError::Color { color: hex, error }),
+    }
+}
+
+/// Theme packs found in the assets, the current one being applied to the board assets
+#[derive(Debug, Clone, Resource)]
+pub struct Themes {
+    /// Theme names, their file names without extension
+    pub names: Vec<String>,
+    /// Labels of the themes shown to the player, in the order of `names`
+    pub labels: Vec<String>,
+    /// Theme the board assets come from
+    pub current: String,
+    /// Handle of the current theme, keeping it loaded and watched for changes
+    pub handle: Handle<Theme>,
+    /// The current theme is still loading, it is applied or reported once done
+    pub loading: bool,
+    /// Folder of the theme packs, the asset server listing and loading every theme in it
+    pub folder: Handle<LoadedFolder>,
+}
+
+impl Themes {
+    /// Loads the theme called `current` and the folder of the theme packs, listed once loaded
+    pub fn load(current: &str, asset_server: &AssetServer) -> Self {
+        Self {
+            names: Vec::new(),
+            labels: Vec::new(),
+            current: current.into(),
+            handle: asset_server.load(Self::path(current)),
+            loading: true,
+            folder: asset_server.load_folder(THEMES_DIR),
+        }
+    }
+
+    /// Lists the theme packs of the loaded `folder`, labelled by their themes once loaded.
+    ///
+    /// Returns whether the list changed
+    pub fn list(&mut self, folder: &LoadedFolder, themes: &Assets<Theme>) -> bool {
+        let suffix = format!(".{}", THEME_EXTENSION);
+        let mut list: Vec<(String, String)> = folder
+            .handles
+            .iter()
+            .filter_map(|handle| {
+                let file_name = handle.path()?.path().file_name()?.to_str()?;
+                let name = file_name.strip_suffix(&suffix)?.to_string();
+                // Themes still loading, or which can't be, show their name
+                let label = handle
+                    .clone()
+                    .try_typed::<Theme>()
+                    .ok()
+                    .and_then(|handle| themes.get(&handle))
+                    .map_or_else(|| name.clone(), |theme| theme.assets.label.clone());
+                Some((name, label))
+            })
+            .collect();
+        list.sort();
+
+        let (names, labels): (Vec<String>, Vec<String>) = list.into_iter().unzip();
+        if names == self.names && labels == self.labels {
+            return false;
+        }
+        self.names = names;
+        self.labels = labels;
+        true
+    }
+
+    /// Asset path of the theme called `name`
+    pub fn path(name: &str) -> String {
+        format!("{}/{}.{}", THEMES_DIR, name, THEME_EXTENSION)
+    }
+
+    /// Makes the theme called `name` current, the board assets changing once it's loaded
+    pub fn select(&mut self, name: &str, asset_server: &AssetServer) {
+        self.current = name.into();
+        self.handle = asset_server.load(Self::path(name));
+        self.loading = true;
+    }
+}
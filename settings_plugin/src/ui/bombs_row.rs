@@ -2,7 +2,7 @@ use bevy::prelude::*;
 
 use super::common::field;
 
-pub fn bombs_row(bomb_count: u16) -> impl Bundle {
+pub fn bombs_row(bomb_count: u32) -> impl Bundle {
     (
         Node {
             width: percent(100.0),
@@ -10,7 +10,8 @@ use crate::events::ChangeInput;
 
 use super::common::label;
 
-pub fn safe_start_row(safe_start: bool) -> impl Bundle {
+/// Labeled checkbox, its label being used to identify the changed option
+pub fn checkbox_row(caption: &'static str, checked: bool) -> impl Bundle {
     (
         Node {
             width: percent(100.0),
@@ -40,19 +41,19 @@ pub fn safe_start_row(safe_start: bool) -> impl Bundle {
                                 ..default()
                             },
                             BorderColor::all(Color::BLACK),
-                            if safe_start {
+                            if checked {
                                 BackgroundColor(Color::from(GREEN))
                             } else {
                                 BackgroundColor(Color::from(GRAY))
                             },
                         ),
-                        label("Safe start")
+                        label(caption)
                     ],
                     observe(checkbox_self_update),
                     observe(on_value_change),
                 ));
 
-                if safe_start {
+                if checked {
                     cmd.insert(Checked);
                 }
             },
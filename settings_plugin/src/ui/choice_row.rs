@@ -0,0 +1,126 @@
+use bevy::{
+    color::palettes::css::{BLUE, GRAY},
+    ecs::relationship::RelatedSpawner,
+    prelude::*,
+    ui::Checked,
+    ui_widgets::{RadioButton, RadioGroup, ValueChange, observe},
+};
+use serde::Serialize;
+
+use crate::{
+    events::{BackOriginalInput, ChangeInput},
+    input_value::InputValue,
+};
+
+use super::common::{label, select_button};
+
+/// Labeled radio group picking one of the `choices`, the chosen value being sent as RON
+pub fn choice_row<V>(
+    caption: &'static str,
+    choices: &'static [(&'static str, V)],
+    selected: V,
+) -> impl Bundle
+where
+    V: Serialize + PartialEq + Copy + Send + Sync + 'static,
+{
+    (
+        Node {
+            width: percent(100.0),
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::SpaceEvenly,
+            column_gap: px(16),
+            ..default()
+        },
+        Children::spawn((
+            Spawn(label(caption)),
+            Spawn((
+                Name::new("Button Group"),
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    column_gap: px(8),
+                    ..default()
+                },
+                RadioGroup,
+                Children::spawn(SpawnWith(move |sub: &mut RelatedSpawner<'_, ChildOf>| {
+                    for &(choice, value) in choices {
+                        select_button(sub, choice, value == selected, None);
+                    }
+                })),
+                observe(radio_group_update),
+                observe(
+                    move |value_change: On<ValueChange<Entity>>,
+                          query: Query<&Children, With<RadioGroup>>,
+                          mut commands: Commands| {
+                        let children = query.get(value_change.source).unwrap();
+                        let Some(index) = children
+                            .iter()
+                            .position(|child| child == value_change.value)
+                        else {
+                            return;
+                        };
+                        let (_, value) = choices[index];
+
+                        commands.trigger(ChangeInput {
+                            entity: value_change.source,
+                            value: InputValue::from(ron::to_string(&value).unwrap()),
+                            label: Some(caption.into()),
+                        });
+                    },
+                ),
+                // A rejected choice checks the button of the kept value back
+                observe(
+                    move |event: On<BackOriginalInput>,
+                          query: Query<&Children, With<RadioGroup>>,
+                          mut buttons: Query<
+                        (Has<Checked>, &mut BackgroundColor),
+                        With<RadioButton>,
+                    >,
+                          mut commands: Commands| {
+                        let Ok(children) = query.get(event.entity) else {
+                            return;
+                        };
+                        let Some(selected) = choices.iter().position(|(_, value)| {
+                            ron::to_string(value).unwrap() == event.value.as_string()
+                        }) else {
+                            return;
+                        };
+                        for (index, button) in children.iter().enumerate() {
+                            let Ok((is_checked, mut background)) = buttons.get_mut(button) else {
+                                continue;
+                            };
+                            if index == selected && !is_checked {
+                                commands.entity(button).insert(Checked);
+                                *background = BackgroundColor(Color::from(BLUE));
+                            } else if index != selected && is_checked {
+                                commands.entity(button).remove::<Checked>();
+                                *background = BackgroundColor(Color::from(GRAY));
+                            }
+                        }
+                    },
+                ),
+            )),
+        )),
+    )
+}
+
+/// Checks the selected button of a radio group without controlled views
+pub fn radio_group_update(
+    value_change: On<ValueChange<Entity>>,
+    query: Query<&Children, With<RadioGroup>>,
+    mut buttons: Query<(Has<Checked>, &mut BackgroundColor), With<RadioButton>>,
+    mut commands: Commands,
+) {
+    let children = query.get(value_change.source).unwrap();
+    for &child in children {
+        let (is_checked, mut background) = buttons.get_mut(child).unwrap();
+        if child == value_change.value {
+            commands.entity(child).insert(Checked);
+            *background = BackgroundColor(Color::from(BLUE));
+        } else if is_checked {
+            commands.entity(child).remove::<Checked>();
+            *background = BackgroundColor(Color::from(GRAY));
+        }
+    }
+}
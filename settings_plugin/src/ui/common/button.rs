@@ -6,6 +6,8 @@ use bevy::{
 use crate::{
     components::SettingsButtonAction,
     events::{BackToMenuEvent, CreateGameEvent},
+    resources::{BoardAssets, SettingsError},
+    ui::root::reload_menu,
 };
 
 use super::text;
@@ -49,10 +51,18 @@ pub fn menu_action(
     >,
     mut create_game: MessageWriter<CreateGameEvent>,
     mut back_to_menu: MessageWriter<BackToMenuEvent>,
+    board_assets: Option<Res<BoardAssets>>,
+    mut commands: Commands,
 ) {
     for (interaction, action) in interaction_query {
         if *interaction == Interaction::Pressed {
             match action {
+                // The board can't be drawn until a theme is loaded
+                SettingsButtonAction::Start if board_assets.is_none() => {
+                    commands
+                        .insert_resource(SettingsError("Pick a theme which can be loaded".into()));
+                    commands.run_system_cached(reload_menu);
+                }
                 SettingsButtonAction::Start => {
                     create_game.write(CreateGameEvent);
                 }
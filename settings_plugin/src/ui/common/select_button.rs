@@ -14,7 +14,7 @@ pub fn select_button(
     parent: &mut RelatedSpawner<'_, ChildOf>,
     caption: &str,
     selected: bool,
-    controls: Entity,
+    controls: Option<Entity>,
 ) {
     let mut cmd = parent.spawn((
         Name::new("Button"),
@@ -28,9 +28,11 @@ pub fn select_button(
         BackgroundColor(Color::from(if selected { BLUE } else { GRAY })),
         RadioButton,
         children![text(24.0, caption)],
-        Controlled::spawn(WithOneRelated(controls)),
     ));
 
+    if let Some(controls) = controls {
+        cmd.insert(Controlled::spawn(WithOneRelated(controls)));
+    }
     if selected {
         cmd.insert(Checked);
     }
@@ -151,30 +151,30 @@ fn on_click_text(
 ) {
     let (parent, text, computed_text) = text_query.get(click.entity).unwrap();
     let buffer = computed_text.buffer();
-    if let Some(position) = click.hit.position {
-        if let (Some(x), Some(y)) = buffer.size() {
-            let local_x = (position.x + 0.5) * x;
-            let local_y = (position.y + 0.5) * y;
-
-            if let Some(cursor) = buffer.hit(local_x, local_y) {
-                let cursor_pos = text
-                    .0
-                    .char_indices()
-                    .position(|(b, _)| b == cursor.index)
-                    .unwrap_or_else(|| text.0.chars().count());
-
-                log::info!(
-                    "local {:?} cursor {:?} cursor_pos {}",
-                    (local_x, local_y),
-                    cursor,
-                    cursor_pos
-                );
-
-                commands.trigger(SetCursorPosEvent {
-                    entity: parent.parent(),
-                    cursor_pos,
-                });
-            }
+    if let Some(position) = click.hit.position
+        && let (Some(x), Some(y)) = buffer.size()
+    {
+        let local_x = (position.x + 0.5) * x;
+        let local_y = (position.y + 0.5) * y;
+
+        if let Some(cursor) = buffer.hit(local_x, local_y) {
+            let cursor_pos = text
+                .0
+                .char_indices()
+                .position(|(b, _)| b == cursor.index)
+                .unwrap_or_else(|| text.0.chars().count());
+
+            log::info!(
+                "local {:?} cursor {:?} cursor_pos {}",
+                (local_x, local_y),
+                cursor,
+                cursor_pos
+            );
+
+            commands.trigger(SetCursorPosEvent {
+                entity: parent.parent(),
+                cursor_pos,
+            });
         }
     }
 }
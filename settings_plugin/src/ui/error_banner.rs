@@ -0,0 +1,29 @@
+use bevy::{color::palettes::css::DARK_RED, prelude::*, ui_widgets::observe};
+
+use crate::resources::SettingsError;
+
+use super::common::text;
+
+/// Banner on top of the settings screen, dismissed by a click
+pub fn error_banner(message: &str) -> impl Bundle {
+    (
+        Name::new("Error Banner"),
+        Node {
+            position_type: PositionType::Absolute,
+            top: px(8),
+            left: px(8),
+            right: px(8),
+            padding: px(8).all(),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BackgroundColor(Color::from(DARK_RED)),
+        children![text(20.0, message)],
+        observe(dismiss_error),
+    )
+}
+
+fn dismiss_error(click: On<Pointer<Click>>, mut commands: Commands) {
+    commands.remove_resource::<SettingsError>();
+    commands.entity(click.entity).despawn();
+}
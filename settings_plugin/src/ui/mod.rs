@@ -1,8 +1,14 @@
 mod bombs_row;
+mod checkbox_row;
+mod choice_row;
 pub mod common;
+mod error_banner;
 mod map_size_row;
 mod position_row;
+pub mod preset_row;
+mod profile_row;
 pub mod root;
-mod safe_start_row;
+mod seed_row;
+mod theme_row;
 mod tile_padding_row;
 mod tile_size_row;
@@ -42,8 +42,8 @@ pub fn position_row(pos: &BoardPosition, controls: [Entity; 2]) -> impl Bundle {
                     let is_centered = matches!(&pos, BoardPosition::Centered { .. });
                     let is_custom = matches!(&pos, BoardPosition::Custom { .. });
 
-                    select_button(sub, "Centered", is_centered, controls[0]);
-                    select_button(sub, "Custom", is_custom, controls[1]);
+                    select_button(sub, "Centered", is_centered, Some(controls[0]));
+                    select_button(sub, "Custom", is_custom, Some(controls[1]));
                 })),
                 observe(button_group_update),
                 observe(on_value_change),
@@ -0,0 +1,92 @@
+use bevy::{
+    color::palettes::css::{BLUE, GRAY},
+    prelude::*,
+    ui::Checked,
+    ui_widgets::{RadioButton, RadioGroup},
+};
+
+use crate::{
+    components::{PresetRow, TextInput},
+    events::BackOriginalInput,
+    input_value::InputValue,
+    resources::{BoardOptions, BoardPreset},
+};
+
+use super::choice_row::choice_row;
+
+/// Difficulty presets, in button order
+const PRESETS: &[(&str, BoardPreset)] = &[
+    ("Beginner", BoardPreset::Beginner),
+    ("Intermediate", BoardPreset::Intermediate),
+    ("Expert", BoardPreset::Expert),
+    ("Custom", BoardPreset::Custom),
+];
+
+pub fn preset_row(selected: BoardPreset) -> impl Bundle {
+    (choice_row("Preset", PRESETS, selected), PresetRow)
+}
+
+/// Checks the button of the current preset, which becomes custom once a field is edited
+pub fn show_preset(
+    board: Res<BoardOptions>,
+    rows: Query<&Children, With<PresetRow>>,
+    groups: Query<&Children, With<RadioGroup>>,
+    mut buttons: Query<(Has<Checked>, &mut BackgroundColor), With<RadioButton>>,
+    mut commands: Commands,
+) {
+    let Some(selected) = PRESETS
+        .iter()
+        .position(|&(_, preset)| preset == board.preset)
+    else {
+        return;
+    };
+    let group = rows
+        .iter()
+        .flatten()
+        .find_map(|&child| groups.get(child).ok());
+    for (index, &button) in group.into_iter().flatten().enumerate() {
+        let Ok((is_checked, mut background)) = buttons.get_mut(button) else {
+            continue;
+        };
+        if index == selected && !is_checked {
+            commands.entity(button).insert(Checked);
+            *background = BackgroundColor(Color::from(BLUE));
+        } else if index != selected && is_checked {
+            commands.entity(button).remove::<Checked>();
+            *background = BackgroundColor(Color::from(GRAY));
+        }
+    }
+}
+
+/// Shows the map size and bomb count of a chosen preset in their fields
+pub fn show_preset_values(
+    board: Res<BoardOptions>,
+    labels: Query<(&Name, &ChildOf), With<Label>>,
+    fields: Query<&Children>,
+    inputs: Query<&TextInput>,
+    mut commands: Commands,
+) {
+    for (name, parent) in labels {
+        let value = match name.as_str() {
+            "Width" => InputValue::from(board.map_size.0 as i32),
+            "Height" => InputValue::from(board.map_size.1 as i32),
+            "Bombs" => InputValue::from(board.bomb_count as i32),
+            _ => continue,
+        };
+        let Ok(children) = fields.get(parent.parent()) else {
+            continue;
+        };
+        for &entity in children {
+            // A field being typed in is left alone
+            let Ok(input) = inputs.get(entity) else {
+                continue;
+            };
+            if !input.focused && input.value.as_string() != value.as_string() {
+                commands.trigger(BackOriginalInput {
+                    entity,
+                    value: value.clone(),
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use bevy::{
+    color::palettes::css::GRAY,
+    ecs::relationship::RelatedSpawner,
+    log,
+    prelude::*,
+    ui_widgets::{RadioGroup, ValueChange, observe},
+};
+
+use crate::{
+    events::ChangeInput,
+    input_value::InputValue,
+    resources::{BoardOptions, Profiles, SettingsError},
+};
+
+use super::{
+    choice_row::radio_group_update,
+    common::{field, label, select_button, text},
+    root::reload_menu,
+};
+
+/// Profile picker, loading the chosen profile, and a button deleting the current one
+pub fn profile_row(profiles: &Profiles) -> impl Bundle {
+    let names = profiles.names.clone();
+    let current = profiles.current.clone();
+
+    (
+        Node {
+            width: percent(100.0),
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::SpaceEvenly,
+            column_gap: px(16),
+            ..default()
+        },
+        Children::spawn((
+            Spawn(label("Profile")),
+            Spawn((
+                Name::new("Button Group"),
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    flex_wrap: FlexWrap::Wrap,
+                    align_items: AlignItems::Center,
+                    column_gap: px(8),
+                    row_gap: px(8),
+                    ..default()
+                },
+                RadioGroup,
+                Children::spawn(SpawnWith({
+                    let names = names.clone();
+                    move |sub: &mut RelatedSpawner<'_, ChildOf>| {
+                        for name in &names {
+                            select_button(sub, name, *name == current, None);
+                        }
+                    }
+                })),
+                observe(radio_group_update),
+                observe(
+                    move |value_change: On<ValueChange<Entity>>,
+                          query: Query<&Children, With<RadioGroup>>,
+                          mut commands: Commands| {
+                        let children = query.get(value_change.source).unwrap();
+                        let Some(index) = children
+                            .iter()
+                            .position(|child| child == value_change.value)
+                        else {
+                            return;
+                        };
+
+                        commands.trigger(ChangeInput {
+                            entity: value_change.source,
+                            value: InputValue::from(names[index].clone()),
+                            label: Some("Profile".into()),
+                        });
+                    },
+                ),
+            )),
+            Spawn((
+                Name::new("Delete Profile"),
+                Node {
+                    padding: px(4).all(),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                BackgroundColor(Color::from(GRAY)),
+                Button,
+                children![text(24.0, "Delete")],
+                observe(delete_profile),
+            )),
+        )),
+    )
+}
+
+/// Field saving the current options under the typed profile name
+pub fn save_profile_row() -> impl Bundle {
+    (
+        Node {
+            width: percent(100.0),
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::SpaceEvenly,
+            column_gap: px(16),
+            ..default()
+        },
+        children![field("Save as", String::new())],
+    )
+}
+
+/// Deletes the current profile and loads the default one
+fn delete_profile(
+    click: On<Pointer<Click>>,
+    mut profiles: ResMut<Profiles>,
+    mut board: ResMut<BoardOptions>,
+    mut commands: Commands,
+) {
+    if click.button != PointerButton::Primary {
+        return;
+    }
+    match profiles.delete_current() {
+        Ok(()) => *board = Profiles::read_or_default(&profiles.current, &mut commands),
+        Err(err) => {
+            log::error!("{}", err);
+            commands.insert_resource(SettingsError(err));
+        }
+    }
+    commands.run_system_cached(reload_menu);
+}
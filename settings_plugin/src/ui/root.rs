@@ -1,26 +1,56 @@
-use bevy::{color::palettes::css::GREEN, log, prelude::*};
+use std::error::Error;
+
+use bevy::{
+    color::palettes::css::{GREEN, RED},
+    log,
+    prelude::*,
+};
 
 use crate::{
-    components::{CursorTimer, SettingsButtonAction, SettingsUIRoot, TextInput},
+    components::{CursorTimer, FieldError, SettingsButtonAction, SettingsUIRoot, TextInput},
     events::{BackOriginalInput, ChangeInput, LostFocusEvent},
     input_value::InputValue,
-    resources::BoardOptions,
+    resources::{
+        BoardGeneration, BoardOptions, BoardOptionsError, BoardOptionsField, BoardPreset,
+        BoardTopology, Profiles, SettingsError, Themes,
+    },
 };
 
 use super::{
     bombs_row::bombs_row,
+    checkbox_row::checkbox_row,
+    choice_row::choice_row,
     common::{ButtonPosition, button},
+    error_banner::error_banner,
     map_size_row::map_size_row,
     position_row::{position_row, spawn_board_pos_controls},
-    safe_start_row::safe_start_row,
+    preset_row::preset_row,
+    profile_row::{profile_row, save_profile_row},
+    seed_row::{seed_row, seed_to_string},
+    theme_row::theme_row,
     tile_padding_row::tile_padding_row,
     tile_size_row::{spawn_tile_size_controls, tile_size_row},
 };
 
+/// Generation modes, in button order
+const GENERATIONS: &[(&str, BoardGeneration)] = &[
+    ("Random", BoardGeneration::Random),
+    ("No guess", BoardGeneration::NoGuess),
+];
+
+/// Tile shapes, in button order
+const TOPOLOGIES: &[(&str, BoardTopology)] = &[
+    ("Square", BoardTopology::Square),
+    ("Hexagonal", BoardTopology::Hexagonal),
+];
+
 pub fn create_menu(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     board: Res<BoardOptions>,
+    profiles: Res<Profiles>,
+    themes: Res<Themes>,
+    settings_error: Option<Res<SettingsError>>,
 ) {
     let font: Handle<Font> = asset_server.load("fonts/FiraSans-Bold.ttf");
 
@@ -36,7 +66,7 @@ pub fn create_menu(
     let board_pos_controls = spawn_board_pos_controls(&board.position, &mut commands);
     let tile_size_contols = spawn_tile_size_controls(&board.tile_size, &mut commands);
 
-    commands
+    let root = commands
         .spawn((
             Name::new("Settings UI Root"),
             Node {
@@ -45,18 +75,28 @@ pub fn create_menu(
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
                 flex_direction: FlexDirection::Column,
-                row_gap: px(50),
+                row_gap: px(16),
                 ..default()
             },
             SettingsUIRoot,
             CursorTimer::default(),
             children![
+                profile_row(&profiles),
+                save_profile_row(),
+                preset_row(board.preset),
                 map_size_row(board.map_size),
                 bombs_row(board.bomb_count),
                 position_row(&board.position, board_pos_controls),
                 tile_size_row(&board.tile_size, tile_size_contols),
                 tile_padding_row(board.tile_padding),
-                safe_start_row(board.safe_start),
+                checkbox_row("Safe start", board.safe_start),
+                checkbox_row("First click safe", board.first_click_safe),
+                checkbox_row("Wrap edges", board.wrap_edges),
+                checkbox_row("Question marks", board.question_marks),
+                seed_row(board.seed),
+                choice_row("Generation", GENERATIONS, board.generation),
+                choice_row("Topology", TOPOLOGIES, board.topology),
+                theme_row(&themes, &board.theme),
                 button(
                     "Start",
                     SettingsButtonAction::Start,
@@ -78,13 +118,25 @@ pub fn create_menu(
             ],
         ))
         .observe(focus_handler)
-        .observe(on_change_labeled_input);
+        .observe(on_change_labeled_input)
+        .id();
+    if let Some(settings_error) = settings_error {
+        commands.spawn((error_banner(&settings_error.0), ChildOf(root)));
+    }
 
     commands.entity(font_observer).despawn();
 
     log::info!("Settings menu initialized");
 }
 
+/// Rebuilds the menu, once the options were replaced by the ones of another profile
+pub fn reload_menu(mut commands: Commands, roots: Query<Entity, With<SettingsUIRoot>>) {
+    for root in roots {
+        commands.entity(root).despawn();
+    }
+    commands.run_system_cached(create_menu);
+}
+
 fn focus_handler(
     click: On<Pointer<Click>>,
     inputs: Query<(Entity, &mut TextInput, &mut BorderColor)>,
@@ -100,10 +152,8 @@ fn focus_handler(
         {
             input.focused = true;
             *border = BorderColor::all(GREEN);
-        } else {
-            if input.focused == true {
-                commands.trigger(LostFocusEvent(entity));
-            }
+        } else if input.focused {
+            commands.trigger(LostFocusEvent(entity));
         }
     }
 }
@@ -111,6 +161,9 @@ fn focus_handler(
 fn on_change_labeled_input(
     change: On<ChangeInput>,
     mut board: ResMut<BoardOptions>,
+    mut profiles: ResMut<Profiles>,
+    labels: Query<(&Name, &ChildOf), With<Label>>,
+    field_errors: Query<Entity, With<FieldError>>,
     mut commands: Commands,
 ) {
     log::info!("{:?}", change.event());
@@ -120,24 +173,55 @@ fn on_change_labeled_input(
         None => return,
     };
 
-    let mut res = || {
+    for field_error in field_errors {
+        commands.entity(field_error).despawn();
+    }
+
+    let mut res = || -> Result<(), Box<dyn Error>> {
         match label.as_str() {
+            "Profile" => {
+                if let InputValue::Str(name) = &change.value {
+                    // The picker goes back to the current profile if this one can't be read
+                    match Profiles::read(name) {
+                        Ok(Some(options)) => {
+                            *board = options;
+                            profiles.select(name);
+                        }
+                        Ok(None) => commands
+                            .insert_resource(SettingsError(format!("Profile {} is gone", name))),
+                        Err(err) => commands.insert_resource(SettingsError(err)),
+                    }
+                    commands.run_system_cached(reload_menu);
+                }
+            }
+            "Save as" => {
+                if let InputValue::Str(name) = &change.value {
+                    profiles.save_as(name, &board)?;
+                    commands.run_system_cached(reload_menu);
+                }
+            }
+            "Preset" => {
+                if let InputValue::Str(raw) = &change.value {
+                    let preset: BoardPreset = ron::from_str(raw).map_err(|e| e.to_string())?;
+                    board.set_preset(preset);
+                }
+            }
             "Width" => {
                 if let InputValue::Int(val) = change.value {
                     let width = u16::try_from(val).map_err(|e| e.to_string())?;
-                    return board.set_width(width);
+                    return Ok(board.set_width(width)?);
                 }
             }
             "Height" => {
                 if let InputValue::Int(val) = change.value {
                     let height = u16::try_from(val).map_err(|e| e.to_string())?;
-                    return board.set_height(height);
+                    return Ok(board.set_height(height)?);
                 }
             }
             "Bombs" => {
                 if let InputValue::Int(val) = change.value {
-                    let bombs = u16::try_from(val).map_err(|e| e.to_string())?;
-                    return board.set_bomb_count(bombs);
+                    let bombs = u32::try_from(val).map_err(|e| e.to_string())?;
+                    return Ok(board.set_bomb_count(bombs)?);
                 }
             }
             "Board position" => {
@@ -148,12 +232,12 @@ fn on_change_labeled_input(
             "Tile size" => {
                 if let InputValue::Str(raw) = &change.value {
                     let tile_size = ron::from_str(raw).map_err(|e| e.to_string())?;
-                    return board.set_tile_size(tile_size);
+                    return Ok(board.set_tile_size(tile_size)?);
                 }
             }
             "Tile padding" => {
                 if let InputValue::Float(tile_padding) = change.value {
-                    return board.set_tile_padding(tile_padding);
+                    return Ok(board.set_tile_padding(tile_padding)?);
                 }
             }
             "Safe start" => {
@@ -161,6 +245,47 @@ fn on_change_labeled_input(
                     board.safe_start = safe_start;
                 }
             }
+            "First click safe" => {
+                if let InputValue::Bool(first_click_safe) = change.value {
+                    board.first_click_safe = first_click_safe;
+                }
+            }
+            "Wrap edges" => {
+                if let InputValue::Bool(wrap_edges) = change.value {
+                    board.wrap_edges = wrap_edges;
+                }
+            }
+            "Question marks" => {
+                if let InputValue::Bool(question_marks) = change.value {
+                    board.question_marks = question_marks;
+                }
+            }
+            "Seed" => {
+                if let InputValue::Str(raw) = &change.value {
+                    let raw = raw.trim();
+                    board.seed = if raw.is_empty() {
+                        None
+                    } else {
+                        Some(raw.parse::<u64>().map_err(|e| e.to_string())?)
+                    };
+                }
+            }
+            "Theme" => {
+                if let InputValue::Str(theme) = &change.value {
+                    board.theme = theme.clone();
+                }
+            }
+            "Generation" => {
+                if let InputValue::Str(raw) = &change.value {
+                    let generation = ron::from_str(raw).map_err(|e| e.to_string())?;
+                    return Ok(board.set_generation(generation)?);
+                }
+            }
+            "Topology" => {
+                if let InputValue::Str(raw) = &change.value {
+                    board.topology = ron::from_str(raw).map_err(|e| e.to_string())?;
+                }
+            }
             _ => {}
         }
         Ok(())
@@ -168,12 +293,32 @@ fn on_change_labeled_input(
 
     if let Err(err) = res() {
         log::error!("{}", err);
+        // Board option errors are shown next to the option at fault, which may not be the edited one
+        let at_fault = err
+            .downcast_ref::<BoardOptionsError>()
+            .map_or(label.as_str(), |err| field_label(err.field()));
+        if let Some((_, parent)) = labels.iter().find(|(name, _)| name.as_str() == at_fault) {
+            commands.spawn((
+                Name::new("Field Error"),
+                Text::new(err.to_string()),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::from(RED)),
+                FieldError,
+                ChildOf(parent.parent()),
+            ));
+        }
         let value = match label.as_str() {
             "Width" => InputValue::from(board.map_size.0 as i32),
             "Height" => InputValue::from(board.map_size.1 as i32),
             "Bombs" => InputValue::from(board.bomb_count as i32),
             "Tile padding" => InputValue::from(board.tile_padding),
             "Tile size" => InputValue::from(ron::to_string(&board.tile_size).unwrap()),
+            "Seed" => InputValue::from(seed_to_string(board.seed)),
+            "Generation" => InputValue::from(ron::to_string(&board.generation).unwrap()),
+            "Save as" => InputValue::from(String::new()),
             _ => unreachable!(),
         };
         commands.trigger(BackOriginalInput {
@@ -184,3 +329,15 @@ fn on_change_labeled_input(
 
     log::info!("Updated BoardOptions: {:?}", *board);
 }
+
+/// Label of the input setting a board option
+fn field_label(field: BoardOptionsField) -> &'static str {
+    match field {
+        BoardOptionsField::Width => "Width",
+        BoardOptionsField::Height => "Height",
+        BoardOptionsField::BombCount => "Bombs",
+        BoardOptionsField::Generation => "Generation",
+        BoardOptionsField::TilePadding => "Tile padding",
+        BoardOptionsField::TileSize => "Tile size",
+    }
+}
@@ -0,0 +1,22 @@
+use bevy::prelude::*;
+
+use super::common::field;
+
+pub fn seed_row(seed: Option<u64>) -> impl Bundle {
+    (
+        Node {
+            width: percent(100.0),
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::SpaceEvenly,
+            column_gap: px(16),
+            ..default()
+        },
+        children![field("Seed", seed_to_string(seed))],
+    )
+}
+
+/// An empty field stands for a random seed
+pub fn seed_to_string(seed: Option<u64>) -> String {
+    seed.map(|s| s.to_string()).unwrap_or_default()
+}
@@ -0,0 +1,74 @@
+use bevy::{
+    ecs::relationship::RelatedSpawner,
+    prelude::*,
+    ui_widgets::{RadioGroup, ValueChange, observe},
+};
+
+use crate::{events::ChangeInput, input_value::InputValue, resources::Themes};
+
+use super::{
+    choice_row::radio_group_update,
+    common::{label, select_button},
+};
+
+/// Theme pack picker, the board assets following the chosen theme
+pub fn theme_row(themes: &Themes, selected: &str) -> impl Bundle {
+    let names = themes.names.clone();
+    let labels = themes.labels.clone();
+    let selected = selected.to_string();
+
+    (
+        Node {
+            width: percent(100.0),
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::SpaceEvenly,
+            column_gap: px(16),
+            ..default()
+        },
+        Children::spawn((
+            Spawn(label("Theme")),
+            Spawn((
+                Name::new("Button Group"),
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    flex_wrap: FlexWrap::Wrap,
+                    align_items: AlignItems::Center,
+                    column_gap: px(8),
+                    row_gap: px(8),
+                    ..default()
+                },
+                RadioGroup,
+                Children::spawn(SpawnWith({
+                    let names = names.clone();
+                    move |sub: &mut RelatedSpawner<'_, ChildOf>| {
+                        // Buttons show the labels, the names being what is saved
+                        for (name, label) in names.iter().zip(&labels) {
+                            select_button(sub, label, *name == selected, None);
+                        }
+                    }
+                })),
+                observe(radio_group_update),
+                observe(
+                    move |value_change: On<ValueChange<Entity>>,
+                          query: Query<&Children, With<RadioGroup>>,
+                          mut commands: Commands| {
+                        let children = query.get(value_change.source).unwrap();
+                        let Some(index) = children
+                            .iter()
+                            .position(|child| child == value_change.value)
+                        else {
+                            return;
+                        };
+
+                        commands.trigger(ChangeInput {
+                            entity: value_change.source,
+                            value: InputValue::from(names[index].clone()),
+                            label: Some("Theme".into()),
+                        });
+                    },
+                ),
+            )),
+        )),
+    )
+}
@@ -43,8 +43,8 @@ pub fn tile_size_row(tile_size: &TileSize, controls: [Entity; 2]) -> impl Bundle
                     let is_adaptive = matches!(&tile_size, TileSize::Adaptive { .. });
                     let is_fixed = matches!(&tile_size, TileSize::Fixed { .. });
 
-                    select_button(sub, "Adaptive", is_adaptive, controls[0]);
-                    select_button(sub, "Fixed", is_fixed, controls[1]);
+                    select_button(sub, "Adaptive", is_adaptive, Some(controls[0]));
+                    select_button(sub, "Fixed", is_fixed, Some(controls[1]));
                 })),
                 observe(button_group_update),
                 observe(on_value_change),
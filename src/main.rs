@@ -1,19 +1,27 @@
 use bevy::{input_focus::InputFocus, log, prelude::*, ui_widgets::UiWidgetsPlugins};
-use board_plugin_v2::events::RestartGameEvent;
-use main_menu_plugin::{MainMenuPlugin, events::LoadSettingsEvent};
+use board_plugin_v2::events::{RestartGameEvent, SaveGameEvent};
+use main_menu_plugin::{
+    MainMenuPlugin,
+    events::{ContinueGameEvent, LoadSettingsEvent, WatchReplayEvent},
+};
 use settings_plugin::{
     SettingsPlugin,
     events::{BackToMenuEvent, CreateGameEvent},
+    resources::{BoardAssets, SettingsError},
 };
 
 #[derive(Debug, Clone, Default, Eq, PartialEq, Hash, States)]
 pub enum AppState {
+    /// Waiting for the board assets of the theme
     #[default]
+    Loading,
     MainMenu,
     Settings,
     InGame {
         paused: bool,
     },
+    /// Playing back a replay on the board, without player input
+    Replay,
     Out,
 }
 
@@ -31,7 +39,7 @@ impl ComputedStates for InGame {
 
     fn compute(sources: AppState) -> Option<Self> {
         match sources {
-            AppState::InGame { .. } => Some(InGame),
+            AppState::InGame { .. } | AppState::Replay => Some(InGame),
             _ => None,
         }
     }
@@ -51,8 +59,9 @@ fn main() {
         }),
         UiWidgetsPlugins,
     ));
+    // Hexagonal tiles overlap their neighbors bounding boxes, only opaque pixels are picked
     app.insert_resource(SpritePickingSettings {
-        picking_mode: SpritePickingMode::BoundingBox,
+        picking_mode: SpritePickingMode::AlphaThreshold(0.1),
         ..default()
     });
     app.init_resource::<InputFocus>();
@@ -98,6 +107,7 @@ fn main() {
     // Startup system (cameras) & board
     app.add_systems(Startup, camera_setup);
     // State handling
+    app.add_systems(Update, finish_loading.run_if(in_state(AppState::Loading)));
     app.add_systems(
         Update,
         (
@@ -117,15 +127,30 @@ fn camera_setup(mut commands: Commands) {
     commands.spawn(Camera2d);
 }
 
+/// Opens the main menu once the theme is loaded, or the settings with the error when it can't be,
+/// so that another theme can be picked
+fn finish_loading(
+    board_assets: Option<Res<BoardAssets>>,
+    settings_error: Option<Res<SettingsError>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if board_assets.is_some() {
+        next_state.set(AppState::MainMenu);
+    } else if settings_error.is_some() {
+        next_state.set(AppState::Settings);
+    }
+}
+
 fn state_handler(
     state: Res<State<AppState>>,
     mut next_state: ResMut<NextState<AppState>>,
     mut events: MessageWriter<CreateGameEvent>,
+    mut save_game_writer: MessageWriter<SaveGameEvent>,
     keys: Res<ButtonInput<KeyCode>>,
 ) {
     if keys.just_pressed(KeyCode::KeyC) {
         log::debug!("clearing detected");
-        if let AppState::InGame { .. } = state.get() {
+        if let AppState::InGame { .. } | AppState::Replay = state.get() {
             log::info!("clearing game");
             next_state.set(AppState::MainMenu);
         }
@@ -143,6 +168,17 @@ fn state_handler(
         }
     }
 
+    // Saving uses Q, as S moves the keyboard tile cursor
+    if keys.just_pressed(KeyCode::KeyQ) {
+        log::debug!("saving detected");
+        if let AppState::InGame { .. } = state.get() {
+            log::info!("saving game and quitting");
+            // The board saves the game as it is cleaned up
+            save_game_writer.write(SaveGameEvent);
+            next_state.set(AppState::MainMenu);
+        }
+    }
+
     if keys.just_pressed(KeyCode::Escape) {
         log::debug!("pause toggle detected");
         if let AppState::InGame { paused } = state.get() {
@@ -160,9 +196,21 @@ fn state_handler(
 fn handle_state_game_events(
     mut create_game_reader: MessageReader<CreateGameEvent>,
     mut load_settings_reader: MessageReader<LoadSettingsEvent>,
+    mut continue_game_reader: MessageReader<ContinueGameEvent>,
+    mut watch_replay_reader: MessageReader<WatchReplayEvent>,
     mut back_to_menu_reader: MessageReader<BackToMenuEvent>,
     mut next_state: ResMut<NextState<AppState>>,
+    board_assets: Option<Res<BoardAssets>>,
+    mut commands: Commands,
 ) {
+    // Without a loaded theme the games of the main menu wait in the settings for another one
+    if board_assets.is_none()
+        && (continue_game_reader.read().count() > 0 || watch_replay_reader.read().count() > 0)
+    {
+        log::error!("No theme loaded to play with");
+        commands.insert_resource(SettingsError("Pick a theme which can be loaded".into()));
+        next_state.set(AppState::Settings);
+    }
     for _ev in create_game_reader.read() {
         log::info!("loading game from event");
         next_state.set(AppState::start_game());
@@ -171,6 +219,14 @@ fn handle_state_game_events(
         log::info!("loading settings from event");
         next_state.set(AppState::Settings);
     }
+    for _ev in continue_game_reader.read() {
+        log::info!("continuing saved game");
+        next_state.set(AppState::start_game());
+    }
+    for _ev in watch_replay_reader.read() {
+        log::info!("watching replay");
+        next_state.set(AppState::Replay);
+    }
     for _ev in back_to_menu_reader.read() {
         log::info!("back to menu");
         next_state.set(AppState::MainMenu);
@@ -180,9 +236,16 @@ fn handle_state_game_events(
 fn handle_restart_game_event(
     mut create_game_writer: MessageWriter<CreateGameEvent>,
     mut restart_game_reader: MessageReader<RestartGameEvent>,
+    state: Res<State<AppState>>,
     mut next_state: ResMut<NextState<AppState>>,
 ) {
     for _ev in restart_game_reader.read() {
+        // A finished replay goes back to the menu instead
+        if state.get() == &AppState::Replay {
+            log::info!("replay over");
+            next_state.set(AppState::MainMenu);
+            continue;
+        }
         log::info!("restart game");
         next_state.set(AppState::Out);
         create_game_writer.write(CreateGameEvent);